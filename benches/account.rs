@@ -0,0 +1,107 @@
+extern crate criterion;
+extern crate exgine;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use exgine::{
+    account::{self, fixed_amount, Quantity, Tranx},
+    asset, rate,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Item(u32);
+
+impl asset::Asset for Item {}
+
+type Account = account::Account<Item>;
+type Rate = rate::Rate<Item>;
+
+const SIZES: [u32; 4] = [1, 8, 64, 1024];
+
+// `size` assets, offset so two accounts of the same size overlap by half.
+fn account(size: u32, offset: u32) -> Account {
+    Account::from(
+        (offset..offset + size)
+            .map(|item| (Item(item), fixed_amount(1000 + item as i32)))
+            .collect::<exgine::map::Map<_, _>>(),
+    )
+}
+
+fn ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("account");
+    for size in SIZES.iter().copied() {
+        let (lhs, rhs) = (account(size, 0), account(size, size / 2));
+        group.bench_with_input(BenchmarkId::new("add", size), &size, |b, _| {
+            b.iter(|| black_box(&lhs) + black_box(&rhs))
+        });
+        group.bench_with_input(BenchmarkId::new("sub", size), &size, |b, _| {
+            b.iter(|| black_box(&lhs) - black_box(&rhs))
+        });
+        group.bench_with_input(BenchmarkId::new("mul", size), &size, |b, _| {
+            b.iter(|| black_box(&lhs) * black_box(fixed_amount(3)))
+        });
+        group.bench_with_input(BenchmarkId::new("add_assign", size), &size, |b, _| {
+            let mut acc = lhs.clone();
+            b.iter(|| acc += black_box(&rhs))
+        });
+        let same = lhs.clone();
+        group.bench_with_input(BenchmarkId::new("eq", size), &size, |b, _| {
+            b.iter(|| black_box(&lhs) == black_box(&same))
+        });
+    }
+    group.finish();
+}
+
+fn exchange(c: &mut Criterion) {
+    let mut group = c.benchmark_group("exchange");
+    for size in SIZES.iter().copied() {
+        // The rate touches four assets however large the accounts are.
+        let rate = Rate {
+            credit: account(2, size).assets().clone(),
+            debit: account(2, 0).assets().clone(),
+            fee: None,
+        };
+        // Both sides hold every asset they pay, even at size 1.
+        let (buyer, seller) = (account(size.max(2), 0), account(size.max(2), size));
+        let quantity: Quantity = fixed_amount(1);
+        assert!(matches!(
+            Account::exchange(&rate, quantity, &buyer, &seller),
+            Tranx::Approved(..)
+        ));
+        group.bench_with_input(BenchmarkId::new("exchange", size), &size, |b, _| {
+            b.iter(|| Account::exchange(&rate, quantity, black_box(&buyer), black_box(&seller)))
+        });
+        group.bench_with_input(BenchmarkId::new("can_afford", size), &size, |b, _| {
+            b.iter(|| Account::can_afford(&rate, quantity, black_box(&buyer), black_box(&seller)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("exchange_in_place", size),
+            &size,
+            |b, _| {
+                let (mut buyer, mut seller, mut fees) =
+                    (buyer.clone(), seller.clone(), Account::default());
+                let (paid, delivered) = (
+                    Account::from(rate.debit.clone()),
+                    Account::from(rate.credit.clone()),
+                );
+                assert!(Account::exchange_in_place(
+                    &rate,
+                    quantity,
+                    &mut buyer,
+                    &mut seller,
+                    &mut fees
+                )
+                .is_ok());
+                b.iter(|| {
+                    // Top both sides back up so every iteration is approved.
+                    buyer += &paid;
+                    seller += &delivered;
+                    Account::exchange_in_place(&rate, quantity, &mut buyer, &mut seller, &mut fees)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ops, exchange);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+extern crate criterion;
+extern crate exgine;
+
+#[allow(dead_code)]
+#[path = "../examples/robot_mission/market.rs"]
+mod market;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use exgine::{
+    account::{self, fixed_amount, Tranx},
+    batch::{Batch, Order},
+    hashmap, rate,
+};
+use market::*;
+
+type Asset = RobotMissionAsset;
+type Rate = rate::Rate<RobotMissionAsset>;
+type Account = account::Account<RobotMissionAsset>;
+
+fn agent_default() -> Account {
+    Account::from(hashmap![
+        Asset::MissionTime => fixed_amount(1),
+        Asset::Trust => fixed_amount(10000),
+        Asset::Resource(Resource::Battery) => fixed_amount(10000),
+        Asset::Resource(Resource::RgbSensor) => fixed_amount(10000),
+        Asset::Resource(Resource::ThermalSensor) => fixed_amount(10000),
+        Asset::Resource(Resource::PoseEstimation) => fixed_amount(10000),
+    ])
+}
+
+fn rates_default() -> [Rate; 2] {
+    [
+        Rate {
+            credit: hashmap![Asset::MissionTime => fixed_amount(1)],
+            debit: hashmap![
+                Asset::Resource(Resource::Battery) => fixed_amount(20),
+                Asset::Resource(Resource::ThermalSensor) => fixed_amount(9),
+                Asset::Resource(Resource::RgbSensor) => fixed_amount(3),
+                Asset::Resource(Resource::PoseEstimation) => fixed_amount(1),
+            ],
+            fee: None,
+        },
+        Rate {
+            credit: hashmap![Asset::MissionTime => fixed_amount(1)],
+            debit: hashmap![Asset::Trust => fixed_amount(1)],
+            fee: None,
+        },
+    ]
+}
+
+// The robot_mission loop: every tick the agent buys 1 MissionTime through the
+// first market it can afford, until it can afford none.
+fn lifetime_exchange(rates: &[Rate]) -> Account {
+    let (mut agent, mut mission) = (
+        agent_default(),
+        Account::from(hashmap![Asset::MissionTime => fixed_amount(1000000)]),
+    );
+    'tick: loop {
+        for rate in rates {
+            if let Tranx::Approved(buyer, seller, _) =
+                Account::exchange(rate, fixed_amount(1), &agent, &mission)
+            {
+                agent = buyer;
+                mission = seller;
+                continue 'tick;
+            }
+        }
+        return agent;
+    }
+}
+
+fn lifetime_in_place(rates: &[Rate]) -> Account {
+    let (mut agent, mut mission) = (
+        agent_default(),
+        Account::from(hashmap![Asset::MissionTime => fixed_amount(1000000)]),
+    );
+    let mut fees = Account::default();
+    'tick: loop {
+        for rate in rates {
+            if Account::exchange_in_place(
+                rate,
+                fixed_amount(1),
+                &mut agent,
+                &mut mission,
+                &mut fees,
+            )
+            .is_ok()
+            {
+                continue 'tick;
+            }
+        }
+        return agent;
+    }
+}
+
+fn robot_mission(c: &mut Criterion) {
+    let rates = rates_default();
+    assert!(lifetime_exchange(&rates) == lifetime_in_place(&rates));
+    let mut group = c.benchmark_group("robot_mission");
+    group.sample_size(10);
+    group.bench_function("exchange", |b| b.iter(|| lifetime_exchange(&rates)));
+    group.bench_function("exchange_in_place", |b| {
+        b.iter(|| lifetime_in_place(&rates))
+    });
+    group.finish();
+}
+
+const AGENTS: usize = 10_000;
+
+// One simulation tick: every agent buys 1 MissionTime from the shared mission
+// through the first market it can afford.
+fn tick_exchange(rates: &[Rate], agents: &mut [Account], mission: &mut Account) {
+    for agent in agents.iter_mut() {
+        for rate in rates {
+            if let Tranx::Approved(buyer, seller, _) =
+                Account::exchange(rate, fixed_amount(1), agent, mission)
+            {
+                *agent = buyer;
+                *mission = seller;
+                break;
+            }
+        }
+    }
+}
+
+fn tick_in_place(rates: &[Rate], agents: &mut [Account], mission: &mut Account) {
+    let mut fees = Account::default();
+    for agent in agents.iter_mut() {
+        for rate in rates {
+            if Account::exchange_in_place(rate, fixed_amount(1), agent, mission, &mut fees).is_ok()
+            {
+                break;
+            }
+        }
+    }
+}
+
+fn tick(c: &mut Criterion) {
+    let rates = rates_default();
+    let agents = vec![agent_default(); AGENTS];
+    let mission = Account::from(hashmap![Asset::MissionTime => fixed_amount(1000000)]);
+    let mut group = c.benchmark_group("tick_10k_agents");
+    group.sample_size(10);
+    group.bench_function("exchange", |b| {
+        b.iter_batched(
+            || (agents.clone(), mission.clone()),
+            |(mut agents, mut mission)| tick_exchange(&rates, &mut agents, &mut mission),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("exchange_in_place", |b| {
+        b.iter_batched(
+            || (agents.clone(), mission.clone()),
+            |(mut agents, mut mission)| tick_in_place(&rates, &mut agents, &mut mission),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+// One tick of 10k agents, each trading with a mission of its own, run as a
+// batch. The registry holds every agent followed by every mission.
+fn batch_pairs(c: &mut Criterion) {
+    let rates = rates_default();
+    let mut accounts = vec![agent_default(); AGENTS];
+    accounts.resize(
+        2 * AGENTS,
+        Account::from(hashmap![Asset::MissionTime => fixed_amount(1000000)]),
+    );
+    let mut batch = Batch::new();
+    for agent in 0..AGENTS {
+        batch.push(Order {
+            rate: &rates[agent % rates.len()],
+            quantity: fixed_amount(1),
+            buyer: agent,
+            seller: AGENTS + agent,
+            fee_account: 0,
+        });
+    }
+    let mut group = c.benchmark_group("batch_10k_pairs");
+    group.sample_size(10);
+    group.bench_function("execute", |b| {
+        b.iter_batched(
+            || accounts.clone(),
+            |mut accounts| batch.execute(&mut accounts),
+            BatchSize::LargeInput,
+        )
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_function("execute_parallel", |b| {
+        b.iter_batched(
+            || accounts.clone(),
+            |mut accounts| batch.execute_parallel(&mut accounts),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, robot_mission, tick, batch_pairs);
+criterion_main!(benches);
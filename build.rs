@@ -0,0 +1,39 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    capi();
+}
+
+// Generates the C header into OUT_DIR. `tests/capi.rs` checks it against the
+// copy in `include/exgine.h` and builds its harness against it.
+#[cfg(feature = "capi")]
+fn capi() {
+    use std::{env, path::PathBuf};
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rustc-env=TARGET={}", env::var("TARGET").unwrap());
+    println!("cargo:rustc-env=HOST={}", env::var("HOST").unwrap());
+
+    let config = cbindgen::Config {
+        usize_is_size_t: true,
+        enumeration: cbindgen::EnumConfig {
+            prefix_with_name: true,
+            rename_variants: cbindgen::RenameRule::ScreamingSnakeCase,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(crate_dir.join("src/capi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("EXGINE_H")
+        .with_sys_include("stdint.h")
+        .with_sys_include("stddef.h")
+        .with_no_includes()
+        .generate()
+        .expect("Unable to generate C header")
+        .write_to_file(out_dir.join("exgine.h"));
+}
@@ -0,0 +1,88 @@
+use exgine::account::{Quantity, I32F32};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// A quantity as written in economy files: an integer, or a decimal string
+/// for fractional amounts so they round-trip exactly.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Amount(pub Quantity);
+
+impl Amount {
+    pub fn one() -> Self {
+        Amount(Quantity::Amount(I32F32::from(1)))
+    }
+}
+
+impl From<Quantity> for Amount {
+    fn from(quantity: Quantity) -> Self {
+        Amount(quantity)
+    }
+}
+
+impl From<Amount> for Quantity {
+    fn from(Amount(quantity): Amount) -> Self {
+        quantity
+    }
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<I32F32>()
+            .map(|amount| Amount(Quantity::Amount(amount)))
+            .map_err(|_| s.to_string())
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Amount(Quantity::Amount(amount)) = self;
+        write!(f, "{}", amount)
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Amount(Quantity::Amount(amount)) = self;
+        if amount.frac() == 0 {
+            serializer.serialize_i64(amount.to_num::<i64>())
+        } else {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+struct AmountVisitor;
+
+impl<'de> de::Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an integer or a decimal string")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Amount, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Amount, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Amount, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Amount, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
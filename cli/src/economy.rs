@@ -0,0 +1,316 @@
+use crate::{amount::Amount, error::Error};
+use exgine::{
+    account::{self, Quantity, Tranx},
+    map::Map,
+    rate::{self, Charge, Fee},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+pub type Account = account::Account<String>;
+pub type Rate = rate::Rate<String>;
+pub type Holdings = BTreeMap<String, Amount>;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticks: Option<u64>,
+    /// The account every rate's fee is paid into. Required once a rate
+    /// charges one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub treasury: Option<String>,
+    #[serde(default)]
+    pub accounts: BTreeMap<String, Holdings>,
+    /// Credit limits by account: how far below zero each asset may go.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub credit: BTreeMap<String, Holdings>,
+    #[serde(default)]
+    pub rates: BTreeMap<String, RateDefinition>,
+    #[serde(default, rename = "agent", skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<Agent>,
+    #[serde(default, rename = "step", skip_serializing_if = "Vec::is_empty")]
+    pub script: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateDefinition {
+    #[serde(default)]
+    pub credit: Holdings,
+    #[serde(default)]
+    pub debit: Holdings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<FeeDefinition>,
+}
+
+/// A fee in `asset`, either `flat` per exchange or a `percent` of what the
+/// buyer is debited in it. Exactly one of the two is given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeDefinition {
+    pub asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flat: Option<Amount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<Amount>,
+}
+
+/// Every tick an agent buys `quantity` from `seller` through the first
+/// market it can afford, and dies once it can afford none of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Agent {
+    pub account: String,
+    pub seller: String,
+    pub markets: Vec<String>,
+    #[serde(default = "Amount::one")]
+    pub quantity: Amount,
+}
+
+/// A scripted exchange, run on `tick` or on every tick when it is omitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick: Option<u64>,
+    pub rate: String,
+    pub quantity: Amount,
+    pub buyer: String,
+    pub seller: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Economy {
+    pub ticks: Option<u64>,
+    pub treasury: Option<String>,
+    pub accounts: BTreeMap<String, Account>,
+    pub rates: BTreeMap<String, Rate>,
+    pub agents: Vec<Agent>,
+    pub script: Vec<Step>,
+}
+
+fn to_map(holdings: &Holdings) -> Map<String, Quantity> {
+    holdings
+        .iter()
+        .map(|(asset, amount)| (asset.clone(), (*amount).into()))
+        .collect()
+}
+
+fn to_holdings(assets: &Map<String, Quantity>) -> Holdings {
+    assets
+        .iter()
+        .map(|(asset, quantity)| (asset.clone(), (*quantity).into()))
+        .collect()
+}
+
+fn to_fee(rate: &str, fee: &FeeDefinition) -> Result<Fee<String>, Error> {
+    let charge = match (fee.flat, fee.percent) {
+        (Some(amount), None) => Charge::Flat(amount.into()),
+        (None, Some(percent)) => Charge::Percent(percent.into()),
+        _ => return Err(Error::InvalidFee(rate.to_string())),
+    };
+    Ok(Fee {
+        asset: fee.asset.clone(),
+        charge,
+    })
+}
+
+fn to_fee_definition(fee: &Fee<String>) -> FeeDefinition {
+    let (flat, percent) = match fee.charge {
+        Charge::Flat(amount) => (Some(amount.into()), None),
+        Charge::Percent(percent) => (None, Some(percent.into())),
+    };
+    FeeDefinition {
+        asset: fee.asset.clone(),
+        flat,
+        percent,
+    }
+}
+
+impl Economy {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Economy, Error> {
+        Economy::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(source: &str) -> Result<Economy, Error> {
+        Economy::from_definition(toml::from_str(source)?)
+    }
+
+    pub fn from_definition(definition: Definition) -> Result<Economy, Error> {
+        let mut economy = Economy {
+            ticks: definition.ticks,
+            treasury: definition.treasury,
+            accounts: definition
+                .accounts
+                .iter()
+                .map(|(name, holdings)| (name.clone(), Account::from(to_map(holdings))))
+                .collect(),
+            rates: definition
+                .rates
+                .iter()
+                .map(|(name, rate)| {
+                    let rate = Rate {
+                        credit: to_map(&rate.credit),
+                        debit: to_map(&rate.debit),
+                        fee: rate.fee.as_ref().map(|fee| to_fee(name, fee)).transpose()?,
+                    };
+                    Ok((name.clone(), rate))
+                })
+                .collect::<Result<_, Error>>()?,
+            agents: definition.agents,
+            script: definition.script,
+        };
+        for (name, limits) in &definition.credit {
+            let account = economy
+                .accounts
+                .remove(name)
+                .ok_or_else(|| Error::UnknownAccount(name.clone()))?;
+            let account = limits.iter().fold(account, |account, (asset, limit)| {
+                account.with_credit(asset.clone(), (*limit).into())
+            });
+            economy.accounts.insert(name.clone(), account);
+        }
+        if let Some(treasury) = &economy.treasury {
+            economy.account(treasury)?;
+        }
+        for (name, rate) in &economy.rates {
+            economy.check_fee(name, rate)?;
+        }
+        for agent in &economy.agents {
+            for market in &agent.markets {
+                economy.check_trade(market, &agent.account, &agent.seller)?;
+            }
+        }
+        for step in &economy.script {
+            economy.check_trade(&step.rate, &step.buyer, &step.seller)?;
+        }
+        Ok(economy)
+    }
+
+    pub fn to_definition(&self) -> Definition {
+        Definition {
+            ticks: self.ticks,
+            treasury: self.treasury.clone(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, account)| (name.clone(), to_holdings(account.assets())))
+                .collect(),
+            credit: self
+                .accounts
+                .iter()
+                .filter(|(_, account)| !account.credit_lines().is_empty())
+                .map(|(name, account)| (name.clone(), to_holdings(account.credit_lines())))
+                .collect(),
+            rates: self
+                .rates
+                .iter()
+                .map(|(name, rate)| {
+                    let rate = RateDefinition {
+                        credit: to_holdings(&rate.credit),
+                        debit: to_holdings(&rate.debit),
+                        fee: rate.fee.as_ref().map(to_fee_definition),
+                    };
+                    (name.clone(), rate)
+                })
+                .collect(),
+            agents: self.agents.clone(),
+            script: self.script.clone(),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, toml::to_string(&self.to_definition())?)?;
+        Ok(())
+    }
+
+    pub fn account(&self, name: &str) -> Result<&Account, Error> {
+        self.accounts
+            .get(name)
+            .ok_or_else(|| Error::UnknownAccount(name.to_string()))
+    }
+
+    pub fn rate(&self, name: &str) -> Result<&Rate, Error> {
+        self.rates
+            .get(name)
+            .ok_or_else(|| Error::UnknownRate(name.to_string()))
+    }
+
+    pub fn create_account(&mut self, name: &str, account: Account) -> Result<(), Error> {
+        if self.accounts.contains_key(name) {
+            return Err(Error::AccountExists(name.to_string()));
+        }
+        self.accounts.insert(name.to_string(), account);
+        Ok(())
+    }
+
+    fn check_fee(&self, name: &str, rate: &Rate) -> Result<(), Error> {
+        match (&rate.fee, &self.treasury) {
+            (Some(_), None) => Err(Error::NoTreasury(name.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `buyer` can trade with `seller` through `rate`, and returns
+    /// the treasury when the rate charges a fee.
+    fn check_trade(&self, rate: &str, buyer: &str, seller: &str) -> Result<Option<String>, Error> {
+        let fee = &self.rate(rate)?.fee;
+        self.account(buyer)?;
+        self.account(seller)?;
+        if buyer == seller {
+            return Err(Error::SameAccount(buyer.to_string()));
+        }
+        let treasury = match fee {
+            Some(_) => Some(
+                self.treasury
+                    .clone()
+                    .ok_or_else(|| Error::NoTreasury(rate.to_string()))?,
+            ),
+            None => None,
+        };
+        if let Some(treasury) = treasury.as_ref().filter(|t| *t == buyer || *t == seller) {
+            return Err(Error::SameAccount(treasury.clone()));
+        }
+        Ok(treasury)
+    }
+
+    pub fn register_rate(&mut self, name: &str, rate: Rate) -> Result<(), Error> {
+        self.check_fee(name, &rate)?;
+        self.rates.insert(name.to_string(), rate);
+        Ok(())
+    }
+
+    /// Runs `Account::exchange_with_fee` between two named accounts, paying
+    /// any fee into the treasury, and stores the resulting balances when
+    /// approved. The treasury cannot trade through a rate that charges a fee.
+    pub fn exchange(
+        &mut self,
+        rate: &str,
+        quantity: Quantity,
+        buyer: &str,
+        seller: &str,
+    ) -> Result<Tranx<String>, Error> {
+        let treasury = self.check_trade(rate, buyer, seller)?;
+        let untouched = Account::default();
+        let fee_account = match &treasury {
+            Some(treasury) => self.account(treasury)?,
+            None => &untouched,
+        };
+        let tranx = Account::exchange_with_fee(
+            self.rate(rate)?,
+            quantity,
+            self.account(buyer)?,
+            self.account(seller)?,
+            fee_account,
+        );
+        if let Tranx::Approved(buyer_account, seller_account, fee_account) = &tranx {
+            self.accounts.insert(seller.to_string(), seller_account.clone());
+            self.accounts.insert(buyer.to_string(), buyer_account.clone());
+            if let Some(treasury) = treasury {
+                self.accounts.insert(treasury, fee_account.clone());
+            }
+        }
+        Ok(tranx)
+    }
+}
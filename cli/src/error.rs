@@ -0,0 +1,74 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    UnknownAccount(String),
+    UnknownRate(String),
+    SameAccount(String),
+    AccountExists(String),
+    InvalidAmount(String),
+    InvalidFee(String),
+    NoTreasury(String),
+    Unbounded,
+    Endless(u64),
+    Command(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse(err) => write!(f, "invalid economy: {}", err),
+            Error::Serialize(err) => write!(f, "cannot save economy: {}", err),
+            Error::UnknownAccount(name) => write!(f, "unknown account `{}`", name),
+            Error::UnknownRate(name) => write!(f, "unknown rate `{}`", name),
+            Error::SameAccount(name) => write!(f, "`{}` cannot trade with itself", name),
+            Error::AccountExists(name) => write!(f, "account `{}` already exists", name),
+            Error::InvalidAmount(amount) => write!(f, "invalid amount `{}`", amount),
+            Error::InvalidFee(rate) => {
+                write!(
+                    f,
+                    "rate `{}` needs exactly one of a flat or percent fee",
+                    rate
+                )
+            }
+            Error::NoTreasury(rate) => {
+                write!(
+                    f,
+                    "rate `{}` charges a fee but the economy has no treasury",
+                    rate
+                )
+            }
+            Error::Unbounded => write!(f, "economy has no agents and no tick limit"),
+            Error::Endless(tick) => write!(
+                f,
+                "economy keeps coming back to its state at tick {}, set a tick limit",
+                tick
+            ),
+            Error::Command(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(err: toml::ser::Error) -> Self {
+        Error::Serialize(err)
+    }
+}
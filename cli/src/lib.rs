@@ -0,0 +1,6 @@
+pub mod amount;
+pub mod economy;
+pub mod error;
+pub mod repl;
+pub mod server;
+pub mod simulation;
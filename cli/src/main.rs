@@ -0,0 +1,137 @@
+extern crate exgine_cli;
+
+use clap::{App, AppSettings, Arg, SubCommand};
+use exgine_cli::{
+    economy::Economy,
+    error::Error,
+    repl::{Reply, Session},
+    server::Server,
+    simulation,
+};
+use rustyline::{error::ReadlineError, Editor};
+use std::process;
+
+fn run(path: &str, ticks: Option<u64>) -> Result<(), Error> {
+    let mut economy = Economy::load(path)?;
+    let report = simulation::run(&mut economy, ticks)?;
+
+    for (name, account) in &economy.accounts {
+        println!("{}: {:#?}", name, account);
+    }
+    for agent in &report.agents {
+        if agent.alive {
+            println!(
+                "{} is still alive after {} ticks.",
+                agent.account, agent.lifetime
+            );
+        } else {
+            println!("RIP! {} was alive for {} ticks.", agent.account, agent.lifetime);
+        }
+    }
+    println!(
+        "Simulated {} ticks: {} exchanges approved, {} denied.",
+        report.ticks, report.approved, report.denied
+    );
+    Ok(())
+}
+
+fn repl(path: &str) -> Result<(), Error> {
+    let mut session = Session::new(Economy::load(path)?);
+    let mut editor = Editor::<()>::new();
+    println!("Loaded {}. Type `help` for commands.", path);
+    loop {
+        let line = match editor.readline("exgine> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(Error::Command(err.to_string())),
+        };
+        editor.add_history_entry(line.as_str());
+        match session.execute(&line) {
+            Ok(Reply::Text(text)) if text.is_empty() => (),
+            Ok(Reply::Text(text)) => println!("{}", text),
+            Ok(Reply::Quit) => break,
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+    Ok(())
+}
+
+fn serve(path: Option<&str>, addr: &str) -> Result<(), Error> {
+    let economy = match path {
+        Some(path) => Economy::load(path)?,
+        None => Economy::default(),
+    };
+    let server = Server::bind(addr, economy)?;
+    println!("Serving on {}.", server.local_addr()?);
+    server.run()?;
+    Ok(())
+}
+
+fn main() {
+    let matches = App::new("exgine")
+        .about("Exchange Engine - A Market Exchange Simulator")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Runs an economy until its agents die or the tick limit is reached")
+                .arg(
+                    Arg::with_name("ECONOMY")
+                        .help("Economy definition file (TOML)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("ticks")
+                        .long("ticks")
+                        .short("t")
+                        .takes_value(true)
+                        .help("Overrides the economy's tick limit"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("Explores an economy interactively")
+                .arg(
+                    Arg::with_name("ECONOMY")
+                        .help("Economy definition file (TOML)")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Serves an economy over JSON-RPC for other processes to trade against")
+                .arg(Arg::with_name("ECONOMY").help("Economy definition file (TOML)"))
+                .arg(
+                    Arg::with_name("addr")
+                        .long("addr")
+                        .short("a")
+                        .takes_value(true)
+                        .default_value("127.0.0.1:7878")
+                        .help("Address to listen on"),
+                ),
+        )
+        .get_matches();
+
+    let result = match matches.subcommand() {
+        ("run", Some(matches)) => {
+            let ticks = match matches.value_of("ticks").map(str::parse).transpose() {
+                Ok(ticks) => ticks,
+                Err(err) => {
+                    eprintln!("error: invalid --ticks: {}", err);
+                    process::exit(2);
+                }
+            };
+            run(matches.value_of("ECONOMY").unwrap(), ticks)
+        }
+        ("repl", Some(matches)) => repl(matches.value_of("ECONOMY").unwrap()),
+        ("serve", Some(matches)) => serve(
+            matches.value_of("ECONOMY"),
+            matches.value_of("addr").unwrap(),
+        ),
+        _ => unreachable!(),
+    };
+
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    amount::Amount,
+    economy::{Account, Economy, Rate},
+    error::Error,
+};
+use exgine::{account::Tranx, map::Map, rate::Charge, stats::Distribution};
+use std::collections::BTreeMap;
+
+const HELP: &str = "\
+show accounts                               list every account
+show account <name>                         print an account's balances
+show rates                                  list every rate
+rate <name>                                 print a rate's credit, debit and fee
+stats <asset>                               print how an asset is spread across accounts
+exchange <rate> <quantity> <buyer> <seller> run Account::exchange
+undo                                        revert the last approved exchange
+history                                     list the commands run so far
+save <file>                                 write the session as an economy file
+quit                                        leave the repl";
+
+pub enum Reply {
+    Text(String),
+    Quit,
+}
+
+/// An interactive session over an economy, keeping a snapshot of the
+/// accounts before every approved exchange so it can be undone.
+pub struct Session {
+    economy: Economy,
+    snapshots: Vec<BTreeMap<String, Account>>,
+    history: Vec<String>,
+}
+
+fn holdings<T: Into<Amount> + Copy>(title: &str, assets: &Map<String, T>) -> String {
+    let mut lines: Vec<String> = assets
+        .iter()
+        .map(|(asset, amount)| format!("  {} = {}", asset, (*amount).into()))
+        .collect();
+    lines.sort();
+    lines.insert(0, title.to_string());
+    lines.join("\n")
+}
+
+fn show_account(name: &str, account: &Account) -> String {
+    holdings(name, account.assets())
+}
+
+fn show_rate(name: &str, rate: &Rate) -> String {
+    let shown = format!(
+        "{}\n{}\n{}",
+        name,
+        holdings(" credit", &rate.credit),
+        holdings(" debit", &rate.debit)
+    );
+    match &rate.fee {
+        Some(fee) => match fee.charge {
+            Charge::Flat(amount) => {
+                format!("{}\n fee\n  {} = {}", shown, fee.asset, Amount(amount))
+            }
+            Charge::Percent(percent) => {
+                format!("{}\n fee\n  {} = {}%", shown, fee.asset, Amount(percent))
+            }
+        },
+        None => shown,
+    }
+}
+
+fn show_stats(asset: &str, economy: &Economy) -> String {
+    let distribution = Distribution::of(&asset.to_string(), economy.accounts.iter());
+    let top: Vec<String> = distribution
+        .top(5)
+        .iter()
+        .map(|(name, quantity)| format!("{} = {}", name, Amount::from(*quantity)))
+        .collect();
+    format!(
+        "{}\n  total = {}\n  holders = {} of {}\n  median = {}\n  gini = {:.4}\n  top = {}",
+        asset,
+        distribution.total().map_or_else(
+            || "overflow".to_string(),
+            |total| Amount::from(total).to_string()
+        ),
+        distribution.holder_count(),
+        distribution.balances.len(),
+        Amount::from(distribution.median()),
+        distribution.gini(),
+        top.join(", ")
+    )
+}
+
+impl Session {
+    pub fn new(economy: Economy) -> Self {
+        Session {
+            economy,
+            snapshots: vec![],
+            history: vec![],
+        }
+    }
+
+    pub fn economy(&self) -> &Economy {
+        &self.economy
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<Reply, Error> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let reply = match words.as_slice() {
+            [] => return Ok(Reply::Text(String::new())),
+            ["help"] => HELP.to_string(),
+            ["quit"] | ["exit"] => return Ok(Reply::Quit),
+            ["show", "accounts"] => self
+                .economy
+                .accounts
+                .iter()
+                .map(|(name, account)| show_account(name, account))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ["show", "account", name] => show_account(name, self.economy.account(name)?),
+            ["show", "rates"] => self
+                .economy
+                .rates
+                .iter()
+                .map(|(name, rate)| show_rate(name, rate))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ["rate", name] => show_rate(name, self.economy.rate(name)?),
+            ["stats", asset] => show_stats(asset, &self.economy),
+            ["exchange", rate, quantity, buyer, seller] => {
+                let quantity: Amount = quantity
+                    .parse()
+                    .map_err(|_| Error::InvalidAmount(quantity.to_string()))?;
+                let snapshot = self.economy.accounts.clone();
+                match self
+                    .economy
+                    .exchange(rate, quantity.into(), buyer, seller)?
+                {
+                    Tranx::Approved(buyer_account, seller_account, _) => {
+                        self.snapshots.push(snapshot);
+                        format!(
+                            "Approved.\n{}\n{}",
+                            show_account(buyer, &buyer_account),
+                            show_account(seller, &seller_account)
+                        )
+                    }
+                    Tranx::Denied(deficit) => {
+                        format!("Denied.\n{}", holdings(" deficit", &deficit))
+                    }
+                }
+            }
+            ["undo"] => match self.snapshots.pop() {
+                Some(accounts) => {
+                    self.economy.accounts = accounts;
+                    "Undid the last exchange.".to_string()
+                }
+                None => return Err(Error::Command("nothing to undo".to_string())),
+            },
+            ["history"] => self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(index, line)| format!("{:>4}  {}", index + 1, line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ["save", path] => {
+                self.economy.save(path)?;
+                format!("Saved to {}.", path)
+            }
+            _ => {
+                return Err(Error::Command(format!(
+                    "unknown command `{}`, try `help`",
+                    line.trim()
+                )))
+            }
+        };
+        self.history.push(line.trim().to_string());
+        Ok(Reply::Text(reply))
+    }
+}
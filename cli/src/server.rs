@@ -0,0 +1,166 @@
+use crate::{
+    economy::{Account, Economy, Rate},
+    error::Error,
+};
+use exgine::{
+    account::{fixed_amount, Tranx},
+    map::Map,
+    rate::{Charge, Fee},
+};
+use exgine_client::{
+    from_balances, from_amount,
+    protocol::{
+        self, Balances, Call, Exchange, Request, Response, INVALID_PARAMS, INVALID_REQUEST, LEDGER_ERROR,
+        METHODS, METHOD_NOT_FOUND, PARSE_ERROR,
+    },
+    to_balances,
+};
+use serde_json::Value;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// Serves one authoritative economy over newline-delimited JSON-RPC, with a
+/// thread per connection and every call applied under a single lock.
+pub struct Server {
+    listener: TcpListener,
+    ledger: Arc<Mutex<Economy>>,
+}
+
+impl Server {
+    pub fn bind<A: ToSocketAddrs>(addr: A, economy: Economy) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            ledger: Arc::new(Mutex::new(economy)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn ledger(&self) -> Arc<Mutex<Economy>> {
+        self.ledger.clone()
+    }
+
+    pub fn run(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let ledger = self.ledger.clone();
+            thread::spawn(move || serve(&ledger, stream));
+        }
+        Ok(())
+    }
+}
+
+fn serve(ledger: &Mutex<Economy>, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut response = serde_json::to_string(&respond(ledger, &line))?;
+        response.push('\n');
+        writer.write_all(response.as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn respond(ledger: &Mutex<Economy>, line: &str) -> Response {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(err) => return Response::error(None, PARSE_ERROR, err.to_string()),
+    };
+    let id = value.get("id").and_then(Value::as_u64);
+    if let Some(method) = value.get("method").and_then(Value::as_str) {
+        if !METHODS.contains(&method) {
+            let message = format!("unknown method `{}`", method);
+            return Response::error(id, METHOD_NOT_FOUND, message);
+        }
+    }
+    let request: Request = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(err) => return Response::error(id, INVALID_REQUEST, err.to_string()),
+    };
+    let mut economy = ledger.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match call(&mut economy, request.call) {
+        Ok(result) => Response::result(request.id, result),
+        Err(err @ Error::InvalidAmount(_)) | Err(err @ Error::InvalidFee(_)) => {
+            Response::error(Some(request.id), INVALID_PARAMS, err.to_string())
+        }
+        Err(err) => Response::error(Some(request.id), LEDGER_ERROR, err.to_string()),
+    }
+}
+
+fn invalid_amount(err: exgine_client::Error) -> Error {
+    match err {
+        exgine_client::Error::InvalidAmount(amount) => Error::InvalidAmount(amount),
+        err => Error::Command(err.to_string()),
+    }
+}
+
+fn account(balances: &Balances) -> Result<Account, Error> {
+    let balances = from_balances(balances).map_err(invalid_amount)?;
+    Ok(Account::from(balances.into_iter().collect::<Map<_, _>>()))
+}
+
+fn fee(rate: &str, fee: &protocol::Fee) -> Result<Fee<String>, Error> {
+    let charge = match (&fee.flat, &fee.percent) {
+        (Some(amount), None) => Charge::Flat(from_amount(amount).map_err(invalid_amount)?),
+        (None, Some(percent)) => Charge::Percent(from_amount(percent).map_err(invalid_amount)?),
+        _ => return Err(Error::InvalidFee(rate.to_string())),
+    };
+    Ok(Fee {
+        asset: fee.asset.clone(),
+        charge,
+    })
+}
+
+fn call(economy: &mut Economy, call: Call) -> Result<Value, Error> {
+    match call {
+        Call::CreateAccount { name } => {
+            economy.create_account(&name, Account::default())?;
+            Ok(Value::Null)
+        }
+        Call::Balances { account } => {
+            let balances = to_balances(economy.account(&account)?.assets());
+            Ok(serde_json::to_value(balances).expect("balances serialize"))
+        }
+        Call::RegisterRate {
+            name,
+            credit,
+            debit,
+            fee: charged,
+        } => {
+            let rate = Rate {
+                credit: account(&credit)?.assets().clone(),
+                debit: account(&debit)?.assets().clone(),
+                fee: charged.map(|charged| fee(&name, &charged)).transpose()?,
+            };
+            economy.register_rate(&name, rate)?;
+            Ok(Value::Null)
+        }
+        Call::Exchange {
+            rate,
+            quantity,
+            buyer,
+            seller,
+        } => {
+            let amount = from_amount(&quantity).map_err(invalid_amount)?;
+            if amount <= fixed_amount(0) {
+                return Err(Error::InvalidAmount(quantity));
+            }
+            let exchange = match economy.exchange(&rate, amount, &buyer, &seller)? {
+                Tranx::Approved(..) => Exchange::Approved,
+                Tranx::Denied(deficit) => Exchange::Denied {
+                    deficit: to_balances(&deficit),
+                },
+            };
+            Ok(serde_json::to_value(exchange).expect("exchange serializes"))
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    economy::{Account, Economy},
+    error::Error,
+};
+use exgine::account::Tranx;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentReport {
+    pub account: String,
+    pub lifetime: u64,
+    pub alive: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub ticks: u64,
+    pub approved: u64,
+    pub denied: u64,
+    pub agents: Vec<AgentReport>,
+}
+
+impl Report {
+    fn record<T>(&mut self, tranx: &Tranx<T>) -> bool
+    where
+        T: exgine::asset::Asset,
+    {
+        match tranx {
+            Tranx::Approved(..) => {
+                self.approved += 1;
+                true
+            }
+            Tranx::Denied(_) => {
+                self.denied += 1;
+                false
+            }
+        }
+    }
+}
+
+/// Runs the economy for `ticks` (or the economy's own limit), stopping early
+/// once every agent has died. Without a limit, an economy that comes back to
+/// an earlier state after its last scripted tick would run forever, so that
+/// is an error instead. Cycles are caught by comparing each tick against a
+/// snapshot retaken at doubling intervals (Brent's algorithm).
+pub fn run(economy: &mut Economy, ticks: Option<u64>) -> Result<Report, Error> {
+    let limit = ticks.or(economy.ticks);
+    if limit.is_none() && economy.agents.is_empty() {
+        return Err(Error::Unbounded);
+    }
+    let agents = economy.agents.clone();
+    let script = economy.script.clone();
+    let mut report = Report {
+        agents: agents
+            .iter()
+            .map(|agent| AgentReport {
+                account: agent.account.clone(),
+                lifetime: 0,
+                alive: true,
+            })
+            .collect(),
+        ..Report::default()
+    };
+    let scripted = script.iter().filter_map(|step| step.tick).max();
+    let mut snapshot: Option<(u64, BTreeMap<String, Account>, Vec<bool>)> = None;
+    let mut interval = 1;
+    while !matches!(limit, Some(limit) if report.ticks >= limit)
+        && (agents.is_empty() || report.agents.iter().any(|agent| agent.alive))
+    {
+        let tick = report.ticks;
+        for step in script.iter().filter(|step| step.tick.is_none() || step.tick == Some(tick)) {
+            let tranx = economy.exchange(&step.rate, step.quantity.into(), &step.buyer, &step.seller)?;
+            report.record(&tranx);
+        }
+        for (index, agent) in agents.iter().enumerate() {
+            if !report.agents[index].alive {
+                continue;
+            }
+            let mut bought = false;
+            for market in &agent.markets {
+                let tranx =
+                    economy.exchange(market, agent.quantity.into(), &agent.account, &agent.seller)?;
+                if report.record(&tranx) {
+                    bought = true;
+                    break;
+                }
+            }
+            let agent = &mut report.agents[index];
+            if bought {
+                agent.lifetime += 1;
+            } else {
+                agent.alive = false;
+            }
+        }
+        report.ticks += 1;
+        if limit.is_some() || matches!(scripted, Some(scripted) if report.ticks <= scripted) {
+            continue;
+        }
+        let alive: Vec<bool> = report.agents.iter().map(|agent| agent.alive).collect();
+        if let Some((tick, accounts, was_alive)) = &snapshot {
+            if *accounts == economy.accounts && *was_alive == alive {
+                return Err(Error::Endless(*tick));
+            }
+            if report.ticks - tick < interval {
+                continue;
+            }
+        }
+        snapshot = Some((report.ticks, economy.accounts.clone(), alive));
+        interval *= 2;
+    }
+    Ok(report)
+}
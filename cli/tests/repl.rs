@@ -0,0 +1,169 @@
+extern crate exgine_cli;
+
+use exgine::account::fixed_amount;
+use exgine_cli::{
+    economy::Economy,
+    error::Error,
+    repl::{Reply, Session},
+};
+use std::env;
+
+fn session() -> Session {
+    Session::new(
+        Economy::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/economies/robot_mission.toml"
+        ))
+        .unwrap(),
+    )
+}
+
+fn text(reply: Result<Reply, Error>) -> String {
+    match reply.unwrap() {
+        Reply::Text(text) => text,
+        Reply::Quit => panic!("unexpected quit"),
+    }
+}
+
+fn quantity(session: &Session, account: &str, asset: &str) -> exgine::account::Quantity {
+    session
+        .economy()
+        .account(account)
+        .unwrap()
+        .quantity(&asset.to_string())
+}
+
+#[test]
+fn repl_show() {
+    let mut session = session();
+    assert_eq!(
+        text(session.execute("show account mission")),
+        "mission\n  MissionTime = 1000000"
+    );
+    assert_eq!(
+        text(session.execute("rate MissionTimeWithTrust")),
+        "MissionTimeWithTrust\n credit\n  MissionTime = 1\n debit\n  Trust = 1"
+    );
+    assert!(matches!(
+        session.execute("show account nobody"),
+        Err(Error::UnknownAccount(_))
+    ));
+    assert!(matches!(session.execute("dance"), Err(Error::Command(_))));
+    assert!(matches!(session.execute("quit"), Ok(Reply::Quit)));
+}
+
+#[test]
+fn repl_exchange_undo() {
+    let mut session = session();
+    let reply = text(session.execute("exchange MissionTimeWithTrust 5 agent mission"));
+    assert!(reply.starts_with("Approved."));
+    assert_eq!(quantity(&session, "agent", "MissionTime"), fixed_amount(6));
+    assert_eq!(quantity(&session, "mission", "Trust"), fixed_amount(5));
+
+    let reply = text(session.execute("exchange MissionTimeWithTrust 20000 agent mission"));
+    assert_eq!(reply, "Denied.\n deficit\n  Trust = -10005");
+
+    text(session.execute("undo"));
+    assert_eq!(quantity(&session, "agent", "MissionTime"), fixed_amount(1));
+    assert_eq!(quantity(&session, "mission", "Trust"), fixed_amount(0));
+    assert!(matches!(session.execute("undo"), Err(Error::Command(_))));
+
+    assert_eq!(
+        text(session.execute("history")),
+        "   1  exchange MissionTimeWithTrust 5 agent mission\n   \
+         2  exchange MissionTimeWithTrust 20000 agent mission\n   \
+         3  undo"
+    );
+}
+
+#[test]
+fn repl_save() {
+    let mut session = session();
+    text(session.execute("exchange MissionTimeWithTrust 0.5 agent mission"));
+    let path = env::temp_dir().join(format!("exgine-repl-{}.toml", std::process::id()));
+    text(session.execute(&format!("save {}", path.display())));
+    let saved = Economy::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.accounts, session.economy().accounts);
+    assert_eq!(saved.rates, session.economy().rates);
+    assert_eq!(saved.agents.len(), 1);
+}
+
+#[test]
+fn repl_stats() {
+    let mut session = session();
+    assert_eq!(
+        text(session.execute("stats Trust")),
+        "Trust\n  total = 10000\n  holders = 1 of 2\n  median = 5000\n  gini = 0.5000\n  top = agent = 10000, mission = 0"
+    );
+}
+
+const TOLLED: &str = r#"
+treasury = "guild"
+
+[accounts.bank]
+Coin = 100
+
+[accounts.player]
+Gem = 10
+
+[accounts.guild]
+
+[rates.CoinWithGem]
+credit = { Coin = 10 }
+debit = { Gem = 1 }
+fee = { asset = "Gem", percent = 50 }
+"#;
+
+#[test]
+fn repl_fee() {
+    let mut session = Session::new(Economy::parse(TOLLED).unwrap());
+    assert_eq!(
+        text(session.execute("rate CoinWithGem")),
+        "CoinWithGem\n credit\n  Coin = 10\n debit\n  Gem = 1\n fee\n  Gem = 50%"
+    );
+    text(session.execute("exchange CoinWithGem 2 player bank"));
+    assert_eq!(quantity(&session, "player", "Gem"), fixed_amount(7));
+    assert_eq!(quantity(&session, "bank", "Gem"), fixed_amount(2));
+    assert_eq!(quantity(&session, "guild", "Gem"), fixed_amount(1));
+    assert!(matches!(
+        session.execute("exchange CoinWithGem 1 guild bank"),
+        Err(Error::SameAccount(_))
+    ));
+
+    let path = env::temp_dir().join(format!("exgine-repl-fee-{}.toml", std::process::id()));
+    text(session.execute(&format!("save {}", path.display())));
+    let saved = Economy::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(saved.treasury.as_deref(), Some("guild"));
+    assert_eq!(saved.accounts, session.economy().accounts);
+    assert_eq!(saved.rates, session.economy().rates);
+}
+
+#[test]
+fn repl_fee_errors() {
+    let untreasured = TOLLED.replace("treasury = \"guild\"", "");
+    assert!(matches!(
+        Economy::parse(&untreasured),
+        Err(Error::NoTreasury(rate)) if rate == "CoinWithGem"
+    ));
+    let ambiguous = TOLLED.replace("percent = 50", "percent = 50, flat = 1");
+    assert!(matches!(
+        Economy::parse(&ambiguous),
+        Err(Error::InvalidFee(rate)) if rate == "CoinWithGem"
+    ));
+    let missing = TOLLED.replace("treasury = \"guild\"", "treasury = \"nobody\"");
+    assert!(matches!(
+        Economy::parse(&missing),
+        Err(Error::UnknownAccount(_))
+    ));
+
+    let mut economy =
+        Economy::parse(&TOLLED.replace("fee = { asset = \"Gem\", percent = 50 }", "")).unwrap();
+    economy.treasury = None;
+    let rate = Economy::parse(TOLLED).unwrap().rates["CoinWithGem"].clone();
+    assert!(matches!(
+        economy.register_rate("Tolled", rate),
+        Err(Error::NoTreasury(_))
+    ));
+}
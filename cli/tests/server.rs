@@ -0,0 +1,219 @@
+extern crate exgine_cli;
+
+use exgine::{
+    account::{fixed_amount, Account, Quantity, I32F32},
+    hashmap,
+    rate::{Charge, Fee, Rate},
+};
+use exgine_cli::{economy::Economy, server::Server};
+use exgine_client::{protocol, Client, Error, Trade};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpStream},
+    thread,
+};
+
+// Funded accounts come from the economy the server loads.
+fn spawn() -> SocketAddr {
+    let mut economy = Economy::default();
+    economy
+        .create_account(
+            "mission",
+            Account::from(hashmap!["MissionTime".to_string() => fixed_amount(1000)]),
+        )
+        .unwrap();
+    economy
+        .create_account(
+            "agent",
+            Account::from(hashmap!["Trust".to_string() => fixed_amount(10)]),
+        )
+        .unwrap();
+    economy
+        .create_account("treasury", Account::default())
+        .unwrap();
+    economy.treasury = Some("treasury".to_string());
+    let server = Server::bind("127.0.0.1:0", economy).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    addr
+}
+
+fn setup(client: &mut Client) {
+    client
+        .register_rate(
+            "MissionTimeWithTrust",
+            &Rate {
+                credit: hashmap!["MissionTime".to_string() => fixed_amount(1)],
+                debit: hashmap!["Trust".to_string() => Quantity::Amount(I32F32::from_num(2.5))],
+                fee: None,
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn server_exchange() {
+    let addr = spawn();
+    let mut client = Client::connect(addr).unwrap();
+    setup(&mut client);
+
+    assert_eq!(
+        client
+            .exchange("MissionTimeWithTrust", fixed_amount(2), "agent", "mission")
+            .unwrap(),
+        Trade::Approved
+    );
+    assert_eq!(
+        client.exchange("MissionTimeWithTrust", fixed_amount(3), "agent", "mission").unwrap(),
+        Trade::Denied(
+            vec![("Trust".to_string(), Quantity::Amount(I32F32::from_num(-2.5)))]
+                .into_iter()
+                .collect()
+        )
+    );
+
+    let mut other = Client::connect(addr).unwrap();
+    assert_eq!(
+        other.balances("agent").unwrap(),
+        vec![
+            ("MissionTime".to_string(), fixed_amount(2)),
+            ("Trust".to_string(), fixed_amount(5)),
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert_eq!(
+        other.balances("mission").unwrap(),
+        vec![
+            ("MissionTime".to_string(), fixed_amount(998)),
+            ("Trust".to_string(), fixed_amount(5)),
+        ]
+        .into_iter()
+        .collect()
+    );
+}
+
+#[test]
+fn server_errors() {
+    let addr = spawn();
+    let mut client = Client::connect(addr).unwrap();
+    setup(&mut client);
+
+    match client.create_account("agent") {
+        Err(Error::Rpc(err)) => assert_eq!(err.code, protocol::LEDGER_ERROR),
+        _ => panic!("expected duplicate account error"),
+    }
+    for quantity in [0, -1] {
+        match client.exchange("MissionTimeWithTrust", fixed_amount(quantity), "agent", "mission") {
+            Err(Error::Rpc(err)) => assert_eq!(err.code, protocol::INVALID_PARAMS),
+            _ => panic!("expected invalid quantity error"),
+        }
+    }
+    match client.exchange("MissionTimeWithTrust", fixed_amount(1), "nobody", "mission") {
+        Err(Error::Rpc(err)) => assert_eq!(err.message, "unknown account `nobody`"),
+        _ => panic!("expected unknown account error"),
+    }
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    for (request, code) in [
+        ("{not json", protocol::PARSE_ERROR),
+        (
+            r#"{"jsonrpc":"2.0","id":7,"method":"mint","params":{}}"#,
+            protocol::METHOD_NOT_FOUND,
+        ),
+        (
+            r#"{"jsonrpc":"2.0","id":8,"method":"exchange","params":{}}"#,
+            protocol::INVALID_REQUEST,
+        ),
+
+    ]
+    .iter()
+    {
+        writeln!(stream, "{}", request).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let response: protocol::Response = serde_json::from_str(&line).unwrap();
+        assert_eq!(response.error.unwrap().code, *code);
+    }
+}
+
+#[test]
+fn server_seller_shortfall() {
+    let addr = spawn();
+    let mut client = Client::connect(addr).unwrap();
+    setup(&mut client);
+    client.create_account("broke").unwrap();
+    assert_eq!(client.balances("broke").unwrap(), Default::default());
+
+    assert_eq!(
+        client
+            .exchange("MissionTimeWithTrust", fixed_amount(1), "agent", "broke")
+            .unwrap(),
+        Trade::Denied(
+            vec![("MissionTime".to_string(), fixed_amount(-1))]
+                .into_iter()
+                .collect()
+        )
+    );
+    assert_eq!(
+        client.balances("agent").unwrap(),
+        vec![("Trust".to_string(), fixed_amount(10))]
+            .into_iter()
+            .collect()
+    );
+}
+
+#[test]
+fn server_fee() {
+    let addr = spawn();
+    let mut client = Client::connect(addr).unwrap();
+    client
+        .register_rate(
+            "MissionTimeWithFee",
+            &Rate {
+                credit: hashmap!["MissionTime".to_string() => fixed_amount(1)],
+                debit: hashmap!["Trust".to_string() => fixed_amount(2)],
+                fee: Some(Fee {
+                    asset: "Trust".to_string(),
+                    charge: Charge::Flat(Quantity::Amount(I32F32::from_num(0.5))),
+                }),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        client
+            .exchange("MissionTimeWithFee", fixed_amount(2), "agent", "mission")
+            .unwrap(),
+        Trade::Approved
+    );
+    assert_eq!(
+        client.balances("agent").unwrap()["Trust"],
+        Quantity::Amount(I32F32::from_num(5.5))
+    );
+    assert_eq!(
+        client.balances("treasury").unwrap(),
+        vec![("Trust".to_string(), Quantity::Amount(I32F32::from_num(0.5)))]
+            .into_iter()
+            .collect()
+    );
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    for fee in [
+        r#"{"asset":"Trust"}"#,
+        r#"{"asset":"Trust","flat":"1","percent":"5"}"#,
+        r#"{"asset":"Trust","flat":"lots"}"#,
+    ] {
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":9,"method":"register_rate","params":{{"name":"Bad","credit":{{}},"debit":{{}},"fee":{}}}}}"#,
+            fee
+        );
+        writeln!(stream, "{}", request).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let response: protocol::Response = serde_json::from_str(&line).unwrap();
+        assert_eq!(response.error.unwrap().code, protocol::INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,208 @@
+extern crate exgine_cli;
+
+use exgine::account::{fixed_amount, Quantity, I32F32};
+use exgine_cli::{
+    economy::Economy,
+    error::Error,
+    simulation::{self, AgentReport},
+};
+
+fn robot_mission() -> Economy {
+    Economy::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/economies/robot_mission.toml"
+    ))
+    .unwrap()
+}
+
+#[test]
+fn simulation_agent_lifetime_until_death() {
+    let mut economy = robot_mission();
+    let report = simulation::run(&mut economy, None).unwrap();
+    assert_eq!(
+        report.agents,
+        vec![AgentReport {
+            account: "agent".to_string(),
+            lifetime: 10500,
+            alive: false,
+        }]
+    );
+    assert_eq!(report.ticks, 10501);
+    let agent = economy.account("agent").unwrap();
+    assert_eq!(agent.quantity(&"MissionTime".to_string()), fixed_amount(10501));
+    assert_eq!(agent.quantity(&"Trust".to_string()), fixed_amount(0));
+}
+
+#[test]
+fn simulation_tick_limit() {
+    let mut economy = robot_mission();
+    let report = simulation::run(&mut economy, Some(600)).unwrap();
+    assert_eq!(report.ticks, 600);
+    assert_eq!(report.agents[0].lifetime, 600);
+    assert!(report.agents[0].alive);
+    let mission = economy.account("mission").unwrap();
+    assert_eq!(mission.quantity(&"MissionTime".to_string()), fixed_amount(999400));
+    assert_eq!(mission.quantity(&"Trust".to_string()), fixed_amount(100));
+}
+
+#[test]
+fn simulation_scripted_fractional() {
+    let mut economy = Economy::parse(
+        r#"
+        ticks = 4
+
+        [accounts.bank]
+        Coin = 100
+
+        [accounts.player]
+        Gem = 1
+
+        [rates.CoinWithGem]
+        credit = { Coin = 1 }
+        debit = { Gem = "0.25" }
+
+        [[step]]
+        rate = "CoinWithGem"
+        quantity = 1
+        buyer = "player"
+        seller = "bank"
+        "#,
+    )
+    .unwrap();
+    let report = simulation::run(&mut economy, None).unwrap();
+    assert_eq!((report.ticks, report.approved, report.denied), (4, 4, 0));
+    let player = economy.account("player").unwrap();
+    assert_eq!(player.quantity(&"Gem".to_string()), fixed_amount(0));
+    assert_eq!(
+        economy.account("bank").unwrap().quantity(&"Gem".to_string()),
+        Quantity::Amount(I32F32::from(1))
+    );
+}
+
+#[test]
+fn economy_unknown_references() {
+    let source = r#"
+        [accounts.player]
+        Gem = 1
+
+        [[step]]
+        rate = "CoinWithGem"
+        quantity = 1
+        buyer = "player"
+        seller = "bank"
+    "#;
+    match Economy::parse(source) {
+        Err(Error::UnknownRate(rate)) => assert_eq!(rate, "CoinWithGem"),
+        _ => panic!("expected unknown rate"),
+    }
+    let mut economy = Economy::parse("[accounts.player]\nGem = 1\n").unwrap();
+    assert!(matches!(simulation::run(&mut economy, None), Err(Error::Unbounded)));
+}
+
+#[test]
+fn economy_credit_line() {
+    let source = r#"
+        [accounts.bank]
+        Gem = 0
+
+        [accounts.player]
+        Gem = 40
+
+        [credit.bank]
+        Coin = 20
+
+        [rates.GemWithCoin]
+        credit = { Gem = 1 }
+        debit = { Coin = 1 }
+
+        [[step]]
+        rate = "GemWithCoin"
+        quantity = 10
+        buyer = "bank"
+        seller = "player"
+    "#;
+    let mut economy = Economy::parse(source).unwrap();
+    let report = simulation::run(&mut economy, Some(4)).unwrap();
+    assert_eq!((report.approved, report.denied), (2, 2));
+    let bank = economy.account("bank").unwrap();
+    assert_eq!(bank.quantity(&"Coin".to_string()), fixed_amount(-20));
+    assert_eq!(bank.credit(&"Coin".to_string()), fixed_amount(20));
+
+    let saved = economy.to_definition();
+    assert_eq!(saved.credit.len(), 1);
+    let reloaded = Economy::from_definition(saved).unwrap();
+    assert_eq!(reloaded.account("bank").unwrap().credit(&"Coin".to_string()), fixed_amount(20));
+
+    let source = "[accounts.player]\nGem = 1\n\n[credit.bank]\nCoin = 20\n";
+    assert!(matches!(Economy::parse(source), Err(Error::UnknownAccount(name)) if name == "bank"));
+}
+
+#[test]
+fn simulation_endless() {
+    // The trader swaps its coin for the bank's gem and back forever.
+    let source = r#"
+        [accounts.bank]
+        Gem = 1
+
+        [accounts.trader]
+        Coin = 1
+
+        [rates.GemWithCoin]
+        credit = { Gem = 1 }
+        debit = { Coin = 1 }
+
+        [rates.CoinWithGem]
+        credit = { Coin = 1 }
+        debit = { Gem = 1 }
+
+        [[agent]]
+        account = "trader"
+        seller = "bank"
+        markets = ["GemWithCoin", "CoinWithGem"]
+    "#;
+    let mut economy = Economy::parse(source).unwrap();
+    assert!(matches!(
+        simulation::run(&mut economy, None),
+        Err(Error::Endless(1))
+    ));
+
+    let mut economy = Economy::parse(source).unwrap();
+    let report = simulation::run(&mut economy, Some(100)).unwrap();
+    assert_eq!((report.ticks, report.approved), (100, 100));
+    assert!(report.agents[0].alive);
+}
+
+#[test]
+fn economy_same_account() {
+    let step = r#"
+        [accounts.player]
+        Gem = 1
+
+        [rates.CoinWithGem]
+        credit = { Coin = 1 }
+        debit = { Gem = 1 }
+
+        [[step]]
+        tick = 3
+        rate = "CoinWithGem"
+        quantity = 1
+        buyer = "player"
+        seller = "player"
+    "#;
+    assert!(matches!(Economy::parse(step), Err(Error::SameAccount(name)) if name == "player"));
+
+    let agent = r#"
+        [accounts.player]
+        Gem = 1
+
+        [rates.CoinWithGem]
+        credit = { Coin = 1 }
+        debit = { Gem = 1 }
+
+        [[agent]]
+        account = "player"
+        seller = "player"
+        markets = ["CoinWithGem"]
+    "#;
+    assert!(matches!(Economy::parse(agent), Err(Error::SameAccount(name)) if name == "player"));
+}
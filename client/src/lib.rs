@@ -0,0 +1,181 @@
+pub mod protocol;
+
+use exgine::{
+    account::{Quantity, I32F32},
+    rate::{Charge, Fee, Rate},
+};
+use protocol::{Balances, Call, Exchange, Request, Response, RpcError, VERSION};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Rpc(RpcError),
+    InvalidAmount(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Json(err) => write!(f, "malformed message: {}", err),
+            Error::Rpc(err) => write!(f, "{} ({})", err.message, err.code),
+            Error::InvalidAmount(amount) => write!(f, "invalid amount `{}`", amount),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trade {
+    Approved,
+    Denied(BTreeMap<String, Quantity>),
+}
+
+pub fn to_amount(quantity: Quantity) -> String {
+    let Quantity::Amount(amount) = quantity;
+    amount.to_string()
+}
+
+pub fn from_amount(amount: &str) -> Result<Quantity, Error> {
+    amount
+        .parse::<I32F32>()
+        .map(Quantity::Amount)
+        .map_err(|_| Error::InvalidAmount(amount.to_string()))
+}
+
+pub fn to_balances<'a, TAsset, I>(assets: I) -> Balances
+where
+    TAsset: ToString + 'a,
+    I: IntoIterator<Item = (&'a TAsset, &'a Quantity)>,
+{
+    assets
+        .into_iter()
+        .map(|(asset, quantity)| (asset.to_string(), to_amount(*quantity)))
+        .collect()
+}
+
+pub fn to_fee(fee: &Fee<String>) -> protocol::Fee {
+    let (flat, percent) = match fee.charge {
+        Charge::Flat(amount) => (Some(to_amount(amount)), None),
+        Charge::Percent(percent) => (None, Some(to_amount(percent))),
+    };
+    protocol::Fee {
+        asset: fee.asset.clone(),
+        flat,
+        percent,
+    }
+}
+
+pub fn from_balances(balances: &Balances) -> Result<BTreeMap<String, Quantity>, Error> {
+    balances
+        .iter()
+        .map(|(asset, amount)| Ok((asset.clone(), from_amount(amount)?)))
+        .collect()
+}
+
+/// A connection to an exgine server, sending one JSON-RPC request per line.
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    next_id: u64,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Client, Error> {
+        let writer = TcpStream::connect(addr)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Client {
+            reader,
+            writer,
+            next_id: 0,
+        })
+    }
+
+    pub fn create_account(&mut self, name: &str) -> Result<(), Error> {
+        self.call(Call::CreateAccount {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn balances(&mut self, account: &str) -> Result<BTreeMap<String, Quantity>, Error> {
+        let balances: Balances = self.call(Call::Balances {
+            account: account.to_string(),
+        })?;
+        from_balances(&balances)
+    }
+
+    pub fn register_rate(&mut self, name: &str, rate: &Rate<String>) -> Result<(), Error> {
+        self.call(Call::RegisterRate {
+            name: name.to_string(),
+            credit: to_balances(&rate.credit),
+            debit: to_balances(&rate.debit),
+            fee: rate.fee.as_ref().map(to_fee),
+        })
+    }
+
+    pub fn exchange(
+        &mut self,
+        rate: &str,
+        quantity: Quantity,
+        buyer: &str,
+        seller: &str,
+    ) -> Result<Trade, Error> {
+        let exchange = self.call(Call::Exchange {
+            rate: rate.to_string(),
+            quantity: to_amount(quantity),
+            buyer: buyer.to_string(),
+            seller: seller.to_string(),
+        })?;
+        match exchange {
+            Exchange::Approved => Ok(Trade::Approved),
+            Exchange::Denied { deficit } => Ok(Trade::Denied(from_balances(&deficit)?)),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&mut self, call: Call) -> Result<T, Error> {
+        self.next_id += 1;
+        let request = Request {
+            jsonrpc: VERSION.to_string(),
+            id: self.next_id,
+            call,
+        };
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        let response: Response = serde_json::from_str(&line)?;
+        match response {
+            Response {
+                error: Some(error), ..
+            } => Err(Error::Rpc(error)),
+            Response { result, .. } => Ok(serde_json::from_value(
+                result.unwrap_or(serde_json::Value::Null),
+            )?),
+        }
+    }
+}
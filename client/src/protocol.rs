@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub const VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const LEDGER_ERROR: i64 = -32000;
+
+/// Every method a `Call` can name.
+pub const METHODS: [&str; 4] = ["create_account", "balances", "register_rate", "exchange"];
+
+/// Balances on the wire, keyed by asset with decimal string amounts so the
+/// fixed-point values survive the trip exactly.
+pub type Balances = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    pub id: u64,
+    #[serde(flatten)]
+    pub call: Call,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Call {
+    /// Opens an empty account. Balances only come from the economy the
+    /// server loads, and from trading.
+    CreateAccount {
+        name: String,
+    },
+    Balances {
+        account: String,
+    },
+    RegisterRate {
+        name: String,
+        credit: Balances,
+        debit: Balances,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fee: Option<Fee>,
+    },
+    Exchange {
+        rate: String,
+        quantity: String,
+        buyer: String,
+        seller: String,
+    },
+}
+
+/// A rate's fee on the wire: paid in `asset`, with exactly one of a `flat`
+/// amount or a `percent` of what the buyer is debited in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fee {
+    pub asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flat: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Exchange {
+    Approved,
+    Denied { deficit: Balances },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl Response {
+    pub fn result(id: u64, result: Value) -> Self {
+        Response {
+            jsonrpc: VERSION.to_string(),
+            id: Some(id),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Option<u64>, code: i64, message: String) -> Self {
+        Response {
+            jsonrpc: VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(RpcError { code, message }),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+//! Re-exports the C API so it builds as a shared library:
+//! `cargo build --features capi --example exgine_capi`.
+
+pub use exgine::capi::*;
@@ -1,7 +1,7 @@
 use exgine::asset;
 use std::time::Instant;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Resource {
     Battery,
     RgbSensor,
@@ -9,7 +9,7 @@ pub enum Resource {
     PoseEstimation,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Reward {
     Score,
     Token,
@@ -18,7 +18,7 @@ pub enum Reward {
     Policy,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum RobotMissionAsset {
     Resource(Resource),
     Reward(Reward),
@@ -29,7 +29,7 @@ pub enum RobotMissionAsset {
 
 impl asset::Asset for RobotMissionAsset {}
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RobotMissionMarket {
     MissionTimeWithResource,
     MissionTimeWithTrust,
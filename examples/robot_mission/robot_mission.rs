@@ -3,10 +3,12 @@ mod market;
 
 use exgine::{
     account::{self, Quantity, fixed_amount, Tranx},
-    hashmap, rate,
+    hashmap,
+    issuer::{Issuance, Issuer},
+    map::Map,
+    rate,
 };
 use market::*;
-use std::collections::HashMap;
 use std::time::Instant;
 
 type Asset = RobotMissionAsset;
@@ -20,7 +22,7 @@ pub struct Agent {
 }
 
 impl Agent {
-    pub fn simulate(&mut self, rates: &HashMap<Market, Rate>, mission: &Account) {
+    pub fn simulate(&mut self, rates: &Map<Market, Rate>, mission: &Account) {
         // Every tick agent should be able to purchase 1 MissionTime.
         // First it tries to purchase MissionTime with its Resource through Market::MissionTimeWithResource.
         // If this fails, it will try to purchase through Market::MissionTimeWithTrust.
@@ -30,18 +32,16 @@ impl Agent {
             Market::MissionTimeWithResource,
             Market::MissionTimeWithTrust,
         ];
-        if let Some(Tranx::Approved(buyer, _)) = exs.iter().find_map(|ex| {
-            match Account::exchange(
-                rates.get(ex).unwrap(),
-                fixed_amount(1),
-                &self.account,
-                mission,
-            ) {
-                Tranx::Denied(_) => None,
-                tranx => Some(tranx),
+        let affordable = exs
+            .iter()
+            .map(|ex| rates.get(ex).unwrap())
+            .find(|rate| Account::can_afford(rate, fixed_amount(1), &self.account, mission));
+        if let Some(rate) = affordable {
+            if let Tranx::Approved(buyer, ..) =
+                Account::exchange(rate, fixed_amount(1), &self.account, mission)
+            {
+                self.account = buyer;
             }
-        }) {
-            self.account = buyer;
         }
         let Quantity::Amount(lifetime_after) = self.account.quantity(&Asset::MissionTime);
         if lifetime_after <= lifetime_before {
@@ -51,9 +51,11 @@ impl Agent {
 }
 
 fn mission_default() -> Account {
-    Account::from(hashmap![
-        Asset::MissionTime => fixed_amount(1000000),
-    ])
+    let mission_time = Issuer::new(Asset::MissionTime, Some(fixed_amount(1000000)));
+    match mission_time.mint(fixed_amount(1000000), &Account::default()) {
+        Issuance::Approved(_, mission) => mission,
+        Issuance::Denied(_) => panic!("MissionTime cap exceeded"),
+    }
 }
 
 fn agent_default() -> Account {
@@ -68,7 +70,7 @@ fn agent_default() -> Account {
     ])
 }
 
-fn rates_default() -> HashMap<Market, Rate> {
+fn rates_default() -> Map<Market, Rate> {
     hashmap![
         Market::MissionTimeWithResource =>
         Rate {
@@ -79,11 +81,13 @@ fn rates_default() -> HashMap<Market, Rate> {
                 Asset::Resource(Resource::RgbSensor) => fixed_amount(3),
                 Asset::Resource(Resource::PoseEstimation) => fixed_amount(1),
             ],
+            fee: None,
         },
         Market::MissionTimeWithTrust =>
         Rate {
             credit: hashmap![Asset::MissionTime => fixed_amount(1)],
             debit: hashmap![Asset::Trust => fixed_amount(1)],
+            fee: None,
         },
     ]
 }
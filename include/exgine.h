@@ -0,0 +1,134 @@
+#ifndef EXGINE_H
+#define EXGINE_H
+
+#include <stdint.h>
+#include <stddef.h>
+
+/**
+ * How `exgine_rate_set_fee` charges the buyer: a fixed amount per exchange,
+ * or a percentage of what it is debited in the fee asset.
+ */
+typedef enum ExgineCharge {
+  EXGINE_CHARGE_FLAT = 0,
+  EXGINE_CHARGE_PERCENT = 1,
+} ExgineCharge;
+
+typedef enum ExgineStatus {
+  EXGINE_STATUS_OK = 0,
+  EXGINE_STATUS_DENIED = 1,
+  EXGINE_STATUS_INVALID_ARGUMENT = 2,
+  EXGINE_STATUS_UNKNOWN_RATE = 3,
+  /**
+   * The engine panicked, for instance on fixed-point overflow in a debug
+   * build. The accounts are left untouched.
+   */
+  EXGINE_STATUS_PANICKED = 4,
+} ExgineStatus;
+
+typedef struct ExgineAccount ExgineAccount;
+
+typedef struct ExgineDenial ExgineDenial;
+
+typedef struct ExgineMarket ExgineMarket;
+
+typedef struct ExgineRate ExgineRate;
+
+/**
+ * Raw bits of an I32F32 fixed-point quantity: the value times 2^32.
+ */
+typedef int64_t ExgineQuantity;
+
+ExgineQuantity exgine_quantity_from_int(int32_t amount);
+
+/**
+ * Writes `amount` to `*quantity`. Fails, leaving it untouched, if `amount` is
+ * NaN or out of range, or `quantity` is null.
+ */
+enum ExgineStatus exgine_quantity_from_double(double amount, ExgineQuantity *quantity);
+
+double exgine_quantity_to_double(ExgineQuantity quantity);
+
+struct ExgineAccount *exgine_account_new(void);
+
+void exgine_account_free(struct ExgineAccount *account);
+
+/**
+ * Returns 0 for missing assets and for a null account or asset.
+ */
+ExgineQuantity exgine_account_quantity(const struct ExgineAccount *account, const char *asset);
+
+/**
+ * Sets one balance, leaving the rest of the account as it is.
+ */
+enum ExgineStatus exgine_account_set_quantity(struct ExgineAccount *account,
+                                              const char *asset,
+                                              ExgineQuantity quantity);
+
+struct ExgineRate *exgine_rate_new(void);
+
+void exgine_rate_free(struct ExgineRate *rate);
+
+enum ExgineStatus exgine_rate_set_credit(struct ExgineRate *rate,
+                                         const char *asset,
+                                         ExgineQuantity quantity);
+
+enum ExgineStatus exgine_rate_set_debit(struct ExgineRate *rate,
+                                        const char *asset,
+                                        ExgineQuantity quantity);
+
+/**
+ * Charges the buyer of every exchange at `rate` a fee in `asset`, replacing
+ * any fee already set.
+ */
+enum ExgineStatus exgine_rate_set_fee(struct ExgineRate *rate,
+                                      const char *asset,
+                                      enum ExgineCharge charge,
+                                      ExgineQuantity quantity);
+
+struct ExgineMarket *exgine_market_new(void);
+
+void exgine_market_free(struct ExgineMarket *market);
+
+/**
+ * Registers a copy of `rate` under `name`, replacing any previous rate.
+ */
+enum ExgineStatus exgine_market_register_rate(struct ExgineMarket *market,
+                                              const char *name,
+                                              const struct ExgineRate *rate);
+
+/**
+ * Runs `Account::exchange_with_fee` with the named rate. When approved,
+ * `buyer`, `seller` and `fee_account` are updated in place. When denied, they
+ * are left untouched and, if `denial` is not null, `*denial` receives a
+ * handle to the deficits. `fee_account` may be null only if the rate charges
+ * no fee, and must differ from both sides. Panics are caught and reported as
+ * `Panicked` rather than unwinding into C.
+ */
+enum ExgineStatus exgine_exchange(const struct ExgineMarket *market,
+                                  const char *rate,
+                                  ExgineQuantity quantity,
+                                  struct ExgineAccount *buyer,
+                                  struct ExgineAccount *seller,
+                                  struct ExgineAccount *fee_account,
+                                  struct ExgineDenial **denial);
+
+void exgine_denial_free(struct ExgineDenial *denial);
+
+size_t exgine_denial_len(const struct ExgineDenial *denial);
+
+/**
+ * The asset of the `index`th deficit, sorted by asset name. The string is
+ * owned by the denial and lives until it is freed.
+ */
+const char *exgine_denial_asset(const struct ExgineDenial *denial, size_t index);
+
+/**
+ * How far beyond its credit limit, after holds, the side paying the `index`th
+ * asset would end up: the buyer for the debit and fee, the seller for the
+ * credit. A negative fee is reported as the fee itself.
+ * Accounts made through this API have no credit lines or holds, so this is
+ * the negative balance that side would be left with.
+ */
+ExgineQuantity exgine_denial_quantity(const struct ExgineDenial *denial, size_t index);
+
+#endif /* EXGINE_H */
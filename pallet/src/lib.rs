@@ -0,0 +1,249 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+// The FRAME macros expand to unit returns and `.into()` calls clippy flags.
+#[allow(clippy::unused_unit, clippy::useless_conversion)]
+#[frame_support::pallet]
+pub mod pallet {
+    use exgine::{
+        account::{Account, Quantity, Tranx, I32F32},
+        asset::Asset,
+        map::Map,
+        rate::Rate,
+    };
+    use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+    use frame_system::pallet_prelude::*;
+    use sp_std::prelude::*;
+
+    use crate::WeightInfo;
+
+    pub type Balances<T> = Vec<(<T as Config>::Asset, I32F32)>;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type Asset: Asset + Parameter + Member;
+        type RateId: Parameter + Member + Copy;
+        /// Most assets a rate may list across credit and debit; bounds the exchange weight.
+        #[pallet::constant]
+        type MaxRateAssets: Get<u32>;
+        /// Most assets an account may hold; bounds the weight of decoding and rewriting it.
+        #[pallet::constant]
+        type MaxAccountAssets: Get<u32>;
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+    /// Registered rates as (credit, debit) per unit of quantity.
+    #[pallet::storage]
+    #[pallet::getter(fn rates)]
+    pub type Rates<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::RateId, (Balances<T>, Balances<T>)>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn accounts)]
+    pub type Accounts<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, Balances<T>>;
+
+    /// Quantity of a rate each seller has agreed to sell, drawn down by exchanges.
+    #[pallet::storage]
+    #[pallet::getter(fn offers)]
+    pub type Offers<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::RateId, I32F32>;
+
+    #[pallet::event]
+    #[pallet::metadata(T::AccountId = "AccountId", T::RateId = "RateId")]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A rate was registered or replaced. [rate]
+        RateRegistered(T::RateId),
+        /// An account was created. [who]
+        AccountCreated(T::AccountId),
+        /// A seller offered a quantity of a rate, or withdrew with zero. [seller, rate, quantity]
+        Offered(T::AccountId, T::RateId, I32F32),
+        /// An exchange was executed. [buyer, seller, rate, quantity]
+        Approved(T::AccountId, T::AccountId, T::RateId, I32F32),
+        /// An exchange was denied and nothing moved. [buyer, seller, rate, quantity, deficit]
+        Denied(T::AccountId, T::AccountId, T::RateId, I32F32, Balances<T>),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        UnknownRate,
+        UnknownAccount,
+        AccountExists,
+        SameAccount,
+        InvalidQuantity,
+        TooManyAssets,
+        /// The same asset is listed twice.
+        DuplicateAsset,
+        /// The seller has not offered this much of the rate.
+        NotOffered,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::weight(T::WeightInfo::register_rate((credit.len() + debit.len()) as u32))]
+        pub fn register_rate(
+            origin: OriginFor<T>,
+            rate: T::RateId,
+            credit: Balances<T>,
+            debit: Balances<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                credit.len() + debit.len() <= T::MaxRateAssets::get() as usize,
+                Error::<T>::TooManyAssets
+            );
+            ensure!(
+                Self::distinct(&credit) && Self::distinct(&debit),
+                Error::<T>::DuplicateAsset
+            );
+            Rates::<T>::insert(rate, (credit, debit));
+            Self::deposit_event(Event::RateRegistered(rate));
+            Ok(().into())
+        }
+
+        #[pallet::weight(T::WeightInfo::create_account(balances.len() as u32))]
+        pub fn create_account(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            balances: Balances<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_root(origin)?;
+            ensure!(
+                balances.len() <= T::MaxAccountAssets::get() as usize,
+                Error::<T>::TooManyAssets
+            );
+            ensure!(Self::distinct(&balances), Error::<T>::DuplicateAsset);
+            ensure!(!Accounts::<T>::contains_key(&who), Error::<T>::AccountExists);
+            Accounts::<T>::insert(&who, balances);
+            Self::deposit_event(Event::AccountCreated(who));
+            Ok(().into())
+        }
+
+        /// Offers up to `quantity` units of `rate` for sale by the caller; zero withdraws the offer.
+        #[pallet::weight(T::WeightInfo::offer())]
+        pub fn offer(
+            origin: OriginFor<T>,
+            rate: T::RateId,
+            quantity: I32F32,
+        ) -> DispatchResultWithPostInfo {
+            let seller = ensure_signed(origin)?;
+            ensure!(quantity >= I32F32::from(0), Error::<T>::InvalidQuantity);
+            ensure!(Rates::<T>::contains_key(rate), Error::<T>::UnknownRate);
+            if quantity == I32F32::from(0) {
+                Offers::<T>::remove(&seller, rate);
+            } else {
+                Offers::<T>::insert(&seller, rate, quantity);
+            }
+            Self::deposit_event(Event::Offered(seller, rate, quantity));
+            Ok(().into())
+        }
+
+        /// Buys `quantity` units of `rate` from `seller`, with the caller as buyer.
+        /// The seller must have offered at least `quantity`; an approved exchange draws it down.
+        /// A denied exchange still succeeds as an extrinsic so the `Denied` event is kept,
+        /// but one that would leave either account with more than `MaxAccountAssets` fails.
+        /// Weighed for the largest rate and accounts up front and refunded down to the assets
+        /// `rate` lists and the assets both accounts hold.
+        #[pallet::weight(T::WeightInfo::exchange(
+            T::MaxRateAssets::get(),
+            T::MaxAccountAssets::get().saturating_mul(2)
+        ))]
+        pub fn exchange(
+            origin: OriginFor<T>,
+            rate: T::RateId,
+            quantity: I32F32,
+            seller: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
+            let buyer = ensure_signed(origin)?;
+            ensure!(quantity > I32F32::from(0), Error::<T>::InvalidQuantity);
+            ensure!(buyer != seller, Error::<T>::SameAccount);
+            let (credit, debit) = Rates::<T>::get(rate).ok_or(Error::<T>::UnknownRate)?;
+            let assets = (credit.len() + debit.len()) as u32;
+            let offered = Offers::<T>::get(&seller, rate).unwrap_or_default();
+            ensure!(offered >= quantity, Error::<T>::NotOffered);
+            let rate_def = Rate {
+                credit: Self::to_map(credit),
+                debit: Self::to_map(debit),
+                fee: None,
+            };
+            let buyer_account = Self::account(&buyer)?;
+            let seller_account = Self::account(&seller)?;
+            let mut held = buyer_account.assets().len() + seller_account.assets().len();
+            match Account::exchange(
+                &rate_def,
+                Quantity::Amount(quantity),
+                &buyer_account,
+                &seller_account,
+            ) {
+                Tranx::Approved(buyer_account, seller_account, _) => {
+                    let max = T::MaxAccountAssets::get() as usize;
+                    ensure!(
+                        buyer_account.assets().len() <= max && seller_account.assets().len() <= max,
+                        Error::<T>::TooManyAssets
+                    );
+                    held = held.max(buyer_account.assets().len() + seller_account.assets().len());
+                    Accounts::<T>::insert(&buyer, Self::to_balances(buyer_account.assets()));
+                    Accounts::<T>::insert(&seller, Self::to_balances(seller_account.assets()));
+                    if offered == quantity {
+                        Offers::<T>::remove(&seller, rate);
+                    } else {
+                        Offers::<T>::insert(&seller, rate, offered - quantity);
+                    }
+                    Self::deposit_event(Event::Approved(buyer, seller, rate, quantity));
+                }
+                Tranx::Denied(deficit) => {
+                    let deficit = Self::to_balances(&deficit);
+                    Self::deposit_event(Event::Denied(buyer, seller, rate, quantity, deficit));
+                }
+            }
+            Ok(Some(T::WeightInfo::exchange(assets, held as u32)).into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn account(who: &T::AccountId) -> Result<Account<T::Asset>, Error<T>> {
+            let balances = Accounts::<T>::get(who).ok_or(Error::<T>::UnknownAccount)?;
+            Ok(Account::from(Self::to_map(balances)))
+        }
+
+        fn distinct(balances: &Balances<T>) -> bool {
+            let mut assets: Vec<&T::Asset> = balances.iter().map(|(asset, _)| asset).collect();
+            assets.sort();
+            assets.windows(2).all(|pair| pair[0] != pair[1])
+        }
+
+        fn to_map(balances: Balances<T>) -> Map<T::Asset, Quantity> {
+            balances
+                .into_iter()
+                .map(|(asset, amount)| (asset, Quantity::Amount(amount)))
+                .collect()
+        }
+
+        // Sorted by asset, so storage and events do not depend on map order.
+        fn to_balances(assets: &Map<T::Asset, Quantity>) -> Balances<T> {
+            let mut balances: Balances<T> = assets
+                .iter()
+                .map(|(asset, Quantity::Amount(amount))| (asset.clone(), *amount))
+                .collect();
+            balances.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            balances
+        }
+    }
+}
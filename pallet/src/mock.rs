@@ -0,0 +1,87 @@
+use crate as pallet_exgine;
+use codec::{Decode, Encode};
+use frame_support::parameter_types;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Exgine: pallet_exgine::{Module, Call, Storage, Event<T>},
+    }
+);
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum MissionAsset {
+    MissionTime,
+    Trust,
+    Battery,
+}
+
+impl exgine::asset::Asset for MissionAsset {}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MissionMarket {
+    MissionTimeWithResource,
+    MissionTimeWithTrust,
+}
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+    pub const MaxRateAssets: u32 = 4;
+    pub const MaxAccountAssets: u32 = 2;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+impl pallet_exgine::Config for Test {
+    type Event = Event;
+    type Asset = MissionAsset;
+    type RateId = MissionMarket;
+    type MaxRateAssets = MaxRateAssets;
+    type MaxAccountAssets = MaxAccountAssets;
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap()
+        .into();
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
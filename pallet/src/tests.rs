@@ -0,0 +1,341 @@
+use crate::{mock::*, Error, Event as ExgineEvent, WeightInfo};
+use exgine::account::I32F32;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+const MISSION: u64 = 1;
+const AGENT: u64 = 2;
+
+fn amount(amount: i32) -> I32F32 {
+    I32F32::from(amount)
+}
+
+fn last_event() -> Event {
+    System::events().pop().expect("event expected").event
+}
+
+fn setup() {
+    assert_ok!(Exgine::register_rate(
+        Origin::root(),
+        MissionMarket::MissionTimeWithTrust,
+        vec![(MissionAsset::MissionTime, amount(1))],
+        vec![(MissionAsset::Trust, amount(2))],
+    ));
+    assert_ok!(Exgine::create_account(
+        Origin::root(),
+        MISSION,
+        vec![(MissionAsset::MissionTime, amount(1000))],
+    ));
+    assert_ok!(Exgine::create_account(
+        Origin::root(),
+        AGENT,
+        vec![(MissionAsset::Trust, amount(10))],
+    ));
+    assert_ok!(Exgine::offer(
+        Origin::signed(MISSION),
+        MissionMarket::MissionTimeWithTrust,
+        amount(100),
+    ));
+}
+
+#[test]
+fn exchange_approved() {
+    new_test_ext().execute_with(|| {
+        setup();
+        let info = Exgine::exchange(
+            Origin::signed(AGENT),
+            MissionMarket::MissionTimeWithTrust,
+            amount(5),
+            MISSION,
+        )
+        .unwrap();
+        // Two rate assets, and two accounts of one asset each that end up with two.
+        assert_eq!(info.actual_weight, Some(<() as WeightInfo>::exchange(2, 4)));
+        assert_eq!(
+            Exgine::accounts(AGENT).unwrap(),
+            vec![
+                (MissionAsset::MissionTime, amount(5)),
+                (MissionAsset::Trust, amount(0)),
+            ]
+        );
+        assert_eq!(
+            Exgine::accounts(MISSION).unwrap(),
+            vec![
+                (MissionAsset::MissionTime, amount(995)),
+                (MissionAsset::Trust, amount(10)),
+            ]
+        );
+        assert_eq!(
+            last_event(),
+            Event::pallet_exgine(ExgineEvent::Approved(
+                AGENT,
+                MISSION,
+                MissionMarket::MissionTimeWithTrust,
+                amount(5),
+            ))
+        );
+        assert_eq!(
+            Exgine::offers(MISSION, MissionMarket::MissionTimeWithTrust),
+            Some(amount(95))
+        );
+    });
+}
+
+#[test]
+fn exchange_denied() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_ok!(Exgine::exchange(
+            Origin::signed(AGENT),
+            MissionMarket::MissionTimeWithTrust,
+            amount(6),
+            MISSION,
+        ));
+        assert_eq!(
+            Exgine::accounts(AGENT).unwrap(),
+            vec![(MissionAsset::Trust, amount(10))]
+        );
+        assert_eq!(
+            last_event(),
+            Event::pallet_exgine(ExgineEvent::Denied(
+                AGENT,
+                MISSION,
+                MissionMarket::MissionTimeWithTrust,
+                amount(6),
+                vec![(MissionAsset::Trust, amount(-2))],
+            ))
+        );
+    });
+}
+
+#[test]
+fn exchange_denied_seller_shortfall() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_ok!(Exgine::create_account(
+            Origin::root(),
+            3,
+            vec![(MissionAsset::MissionTime, amount(1))],
+        ));
+        assert_ok!(Exgine::offer(
+            Origin::signed(3),
+            MissionMarket::MissionTimeWithTrust,
+            amount(2),
+        ));
+        assert_ok!(Exgine::exchange(
+            Origin::signed(AGENT),
+            MissionMarket::MissionTimeWithTrust,
+            amount(2),
+            3,
+        ));
+        assert_eq!(
+            Exgine::accounts(3).unwrap(),
+            vec![(MissionAsset::MissionTime, amount(1))]
+        );
+        assert_eq!(
+            last_event(),
+            Event::pallet_exgine(ExgineEvent::Denied(
+                AGENT,
+                3,
+                MissionMarket::MissionTimeWithTrust,
+                amount(2),
+                vec![(MissionAsset::MissionTime, amount(-1))],
+            ))
+        );
+    });
+}
+
+#[test]
+fn exchange_requires_offer() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(MISSION),
+                MissionMarket::MissionTimeWithTrust,
+                amount(1),
+                AGENT,
+            ),
+            Error::<Test>::NotOffered
+        );
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(AGENT),
+                MissionMarket::MissionTimeWithTrust,
+                amount(101),
+                MISSION,
+            ),
+            Error::<Test>::NotOffered
+        );
+        assert_ok!(Exgine::offer(
+            Origin::signed(MISSION),
+            MissionMarket::MissionTimeWithTrust,
+            amount(0),
+        ));
+        assert_eq!(
+            Exgine::offers(MISSION, MissionMarket::MissionTimeWithTrust),
+            None
+        );
+        assert_noop!(
+            Exgine::offer(
+                Origin::signed(MISSION),
+                MissionMarket::MissionTimeWithTrust,
+                amount(-1),
+            ),
+            Error::<Test>::InvalidQuantity
+        );
+    });
+}
+
+#[test]
+fn exchange_non_positive_quantity() {
+    new_test_ext().execute_with(|| {
+        setup();
+        for &quantity in &[amount(0), amount(-5)] {
+            assert_noop!(
+                Exgine::exchange(
+                    Origin::signed(AGENT),
+                    MissionMarket::MissionTimeWithTrust,
+                    quantity,
+                    MISSION,
+                ),
+                Error::<Test>::InvalidQuantity
+            );
+        }
+    });
+}
+
+#[test]
+fn exchange_unknown_rate_or_account() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(AGENT),
+                MissionMarket::MissionTimeWithResource,
+                amount(1),
+                MISSION,
+            ),
+            Error::<Test>::UnknownRate
+        );
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(3),
+                MissionMarket::MissionTimeWithTrust,
+                amount(1),
+                MISSION,
+            ),
+            Error::<Test>::UnknownAccount
+        );
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(AGENT),
+                MissionMarket::MissionTimeWithTrust,
+                amount(1),
+                AGENT,
+            ),
+            Error::<Test>::SameAccount
+        );
+    });
+}
+
+#[test]
+fn registry_requires_root() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_noop!(
+            Exgine::register_rate(
+                Origin::signed(AGENT),
+                MissionMarket::MissionTimeWithResource,
+                vec![],
+                vec![],
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Exgine::register_rate(
+                Origin::root(),
+                MissionMarket::MissionTimeWithResource,
+                vec![
+                    (MissionAsset::MissionTime, amount(1)),
+                    (MissionAsset::Trust, amount(1)),
+                ],
+                vec![
+                    (MissionAsset::Battery, amount(1)),
+                    (MissionAsset::Trust, amount(1)),
+                    (MissionAsset::MissionTime, amount(1)),
+                ],
+            ),
+            Error::<Test>::TooManyAssets
+        );
+        assert_noop!(
+            Exgine::create_account(Origin::signed(AGENT), 3, vec![]),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Exgine::create_account(Origin::root(), AGENT, vec![]),
+            Error::<Test>::AccountExists
+        );
+    });
+}
+
+#[test]
+fn account_assets_bounded() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_noop!(
+            Exgine::create_account(
+                Origin::root(),
+                3,
+                vec![
+                    (MissionAsset::MissionTime, amount(1)),
+                    (MissionAsset::Trust, amount(1)),
+                    (MissionAsset::Battery, amount(1)),
+                ],
+            ),
+            Error::<Test>::TooManyAssets
+        );
+        assert_noop!(
+            Exgine::create_account(
+                Origin::root(),
+                3,
+                vec![
+                    (MissionAsset::Trust, amount(1)),
+                    (MissionAsset::Trust, amount(1)),
+                ],
+            ),
+            Error::<Test>::DuplicateAsset
+        );
+        assert_noop!(
+            Exgine::register_rate(
+                Origin::root(),
+                MissionMarket::MissionTimeWithResource,
+                vec![(MissionAsset::MissionTime, amount(1))],
+                vec![
+                    (MissionAsset::Battery, amount(1)),
+                    (MissionAsset::Battery, amount(1)),
+                ],
+            ),
+            Error::<Test>::DuplicateAsset
+        );
+
+        // Buying MissionTime would give the buyer a third asset.
+        assert_ok!(Exgine::create_account(
+            Origin::root(),
+            3,
+            vec![
+                (MissionAsset::Trust, amount(10)),
+                (MissionAsset::Battery, amount(1)),
+            ],
+        ));
+        assert_noop!(
+            Exgine::exchange(
+                Origin::signed(3),
+                MissionMarket::MissionTimeWithTrust,
+                amount(1),
+                MISSION,
+            ),
+            Error::<Test>::TooManyAssets
+        );
+    });
+}
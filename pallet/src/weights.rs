@@ -0,0 +1,36 @@
+//! Weights for `pallet-exgine`, scaled by the number of assets an extrinsic touches
+//! and, for exchanges, by the assets stored in the accounts it decodes and rewrites.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+
+pub trait WeightInfo {
+    fn register_rate(assets: u32) -> Weight;
+    fn create_account(assets: u32) -> Weight;
+    fn offer() -> Weight;
+    fn exchange(assets: u32, account_assets: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+    fn register_rate(assets: u32) -> Weight {
+        (10_000 as Weight)
+            .saturating_add((1_000 as Weight).saturating_mul(assets as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1))
+    }
+
+    fn create_account(assets: u32) -> Weight {
+        (10_000 as Weight)
+            .saturating_add((1_000 as Weight).saturating_mul(assets as Weight))
+            .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+    }
+
+    fn offer() -> Weight {
+        (10_000 as Weight).saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+    }
+
+    fn exchange(assets: u32, account_assets: u32) -> Weight {
+        (20_000 as Weight)
+            .saturating_add((5_000 as Weight).saturating_mul(assets as Weight))
+            .saturating_add((2_000 as Weight).saturating_mul(account_assets as Weight))
+            .saturating_add(RocksDbWeight::get().reads_writes(4, 3))
+    }
+}
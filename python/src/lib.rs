@@ -0,0 +1,497 @@
+use exgine::{
+    account::{fixed_amount, Account, Quantity, Tranx, I32F32},
+    map::Map,
+    rate::{Charge, Fee, Rate},
+};
+use exgine_cli::{
+    economy::Economy,
+    error::Error,
+    simulation::{self, AgentReport, Report},
+};
+use pyo3::{
+    basic::CompareOp,
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+    types::{PyBool, PyString},
+};
+use std::collections::BTreeMap;
+
+fn error(err: Error) -> PyErr {
+    match err {
+        Error::Io(err) => PyIOError::new_err(err.to_string()),
+        err => PyValueError::new_err(err.to_string()),
+    }
+}
+
+/// The exact decimal expansion of a fixed-point amount. Every I32F32 has a
+/// finite one, at most 32 fractional digits long.
+fn exact(amount: I32F32) -> String {
+    let bits = amount.to_bits();
+    let magnitude = bits.unsigned_abs();
+    let integer = magnitude >> 32;
+    let fraction = u128::from(magnitude & 0xFFFF_FFFF) * 5u128.pow(32);
+    let sign = if bits < 0 { "-" } else { "" };
+    if fraction == 0 {
+        format!("{}{}", sign, integer)
+    } else {
+        let fraction = format!("{:032}", fraction);
+        format!("{}{}.{}", sign, integer, fraction.trim_end_matches('0'))
+    }
+}
+
+fn extract_quantity(value: &Bound<PyAny>) -> PyResult<Quantity> {
+    if let Ok(PyQuantity(quantity)) = value.extract::<PyQuantity>() {
+        return Ok(quantity);
+    }
+    if let Ok(amount) = value.extract::<i32>() {
+        return Ok(fixed_amount(amount));
+    }
+    let text = value.str()?.to_string();
+    text.trim()
+        .parse::<I32F32>()
+        .map(Quantity::Amount)
+        .map_err(|_| PyValueError::new_err(format!("invalid quantity `{}`", text)))
+}
+
+fn extract_assets(
+    assets: Option<BTreeMap<String, Bound<PyAny>>>,
+) -> PyResult<Map<String, Quantity>> {
+    assets
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(asset, value)| Ok((asset, extract_quantity(&value)?)))
+        .collect()
+}
+
+fn to_dict(assets: &Map<String, Quantity>) -> BTreeMap<String, PyQuantity> {
+    assets
+        .iter()
+        .map(|(asset, quantity)| (asset.clone(), PyQuantity(*quantity)))
+        .collect()
+}
+
+/// A fixed-point quantity. Accepts an int, a `decimal.Decimal`, a string or
+/// another `Quantity`, and converts back to `decimal.Decimal` exactly.
+#[pyclass(name = "Quantity")]
+#[derive(Clone, Copy)]
+pub struct PyQuantity(Quantity);
+
+#[pymethods]
+impl PyQuantity {
+    #[new]
+    fn new(value: &Bound<PyAny>) -> PyResult<Self> {
+        Ok(PyQuantity(extract_quantity(value)?))
+    }
+
+    #[getter]
+    fn bits(&self) -> i64 {
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        amount.to_bits()
+    }
+
+    #[pyo3(name = "to_decimal")]
+    fn decimal(&self, py: Python) -> PyResult<PyObject> {
+        let decimal = py.import("decimal")?.getattr("Decimal")?;
+        Ok(decimal.call1((self.__str__(),))?.into())
+    }
+
+    fn __str__(&self) -> String {
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        exact(*amount)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Quantity('{}')", self.__str__())
+    }
+
+    /// Hashes like the equal Python number: the int when integral, else the
+    /// exact `fractions.Fraction`, which hashes like an equal float or Decimal.
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        let bits = self.bits();
+        if bits & 0xFFFF_FFFF == 0 {
+            (bits >> 32).into_pyobject(py)?.hash()
+        } else {
+            let fraction = py.import("fractions")?.getattr("Fraction")?;
+            fraction.call1((bits, 1i64 << 32))?.hash()
+        }
+    }
+
+    /// Compares with other quantities and numbers. Strings parse as quantities
+    /// elsewhere but never compare equal, since they hash differently.
+    fn __richcmp__(&self, other: &Bound<PyAny>, op: CompareOp, py: Python) -> PyObject {
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        if other.is_instance_of::<PyString>() {
+            return py.NotImplemented();
+        }
+        match extract_quantity(other) {
+            Ok(Quantity::Amount(other)) => PyBool::new(py, op.matches(amount.cmp(&other)))
+                .to_owned()
+                .into_any()
+                .unbind(),
+            Err(_) => py.NotImplemented(),
+        }
+    }
+
+    fn __add__(&self, other: &Bound<PyAny>) -> PyResult<PyQuantity> {
+        let Quantity::Amount(other) = extract_quantity(other)?;
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        Ok(PyQuantity(Quantity::Amount(*amount + other)))
+    }
+
+    fn __sub__(&self, other: &Bound<PyAny>) -> PyResult<PyQuantity> {
+        let Quantity::Amount(other) = extract_quantity(other)?;
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        Ok(PyQuantity(Quantity::Amount(*amount - other)))
+    }
+
+    fn __neg__(&self) -> PyQuantity {
+        let PyQuantity(Quantity::Amount(amount)) = self;
+        PyQuantity(Quantity::Amount(-*amount))
+    }
+}
+
+#[pyclass(name = "Account")]
+#[derive(Clone)]
+pub struct PyAccount(Account<String>);
+
+#[pymethods]
+impl PyAccount {
+    #[new]
+    #[pyo3(signature = (assets = None))]
+    fn new(assets: Option<BTreeMap<String, Bound<PyAny>>>) -> PyResult<Self> {
+        Ok(PyAccount(Account::from(extract_assets(assets)?)))
+    }
+
+    fn quantity(&self, asset: String) -> PyQuantity {
+        PyQuantity(self.0.quantity(&asset))
+    }
+
+    fn assets(&self) -> BTreeMap<String, PyQuantity> {
+        to_dict(self.0.assets())
+    }
+
+    fn __repr__(&self) -> String {
+        let assets: Vec<String> = self
+            .0
+            .assets()
+            .iter()
+            .map(|(asset, Quantity::Amount(amount))| format!("'{}': '{}'", asset, exact(*amount)))
+            .collect();
+        format!("Account({{{}}})", assets.join(", "))
+    }
+
+    /// Accounts compare equal by balance, and are neither ordered nor equal to
+    /// anything else.
+    fn __richcmp__(&self, other: &Bound<PyAny>, op: CompareOp, py: Python) -> PyObject {
+        let other = match other.downcast::<PyAccount>() {
+            Ok(other) => other.borrow(),
+            Err(_) => return py.NotImplemented(),
+        };
+        let equal = match op {
+            CompareOp::Eq => self.0 == other.0,
+            CompareOp::Ne => self.0 != other.0,
+            _ => return py.NotImplemented(),
+        };
+        PyBool::new(py, equal).to_owned().into_any().unbind()
+    }
+
+    /// Pays the rate's fee, if any, into `fee_account`, which starts empty
+    /// when omitted.
+    #[staticmethod]
+    #[pyo3(signature = (rate, quantity, buyer, seller, fee_account = None))]
+    fn exchange(
+        rate: &PyRate,
+        quantity: &Bound<PyAny>,
+        buyer: &PyAccount,
+        seller: &PyAccount,
+        fee_account: Option<&PyAccount>,
+    ) -> PyResult<PyTranx> {
+        let untouched = Account::default();
+        let tranx = Account::exchange_with_fee(
+            &rate.0,
+            extract_quantity(quantity)?,
+            &buyer.0,
+            &seller.0,
+            fee_account.map_or(&untouched, |account| &account.0),
+        );
+        Ok(PyTranx::from(tranx))
+    }
+}
+
+/// Paid by the buyer in `asset`: exactly one of a `flat` amount per exchange
+/// or a `percent` of what the buyer is debited in it.
+#[pyclass(name = "Fee")]
+#[derive(Clone)]
+pub struct PyFee(Fee<String>);
+
+#[pymethods]
+impl PyFee {
+    #[new]
+    #[pyo3(signature = (asset, flat = None, percent = None))]
+    fn new(
+        asset: String,
+        flat: Option<Bound<PyAny>>,
+        percent: Option<Bound<PyAny>>,
+    ) -> PyResult<Self> {
+        let charge = match (flat, percent) {
+            (Some(flat), None) => Charge::Flat(extract_quantity(&flat)?),
+            (None, Some(percent)) => Charge::Percent(extract_quantity(&percent)?),
+            _ => {
+                return Err(PyValueError::new_err(
+                    "a fee needs exactly one of `flat` and `percent`",
+                ))
+            }
+        };
+        Ok(PyFee(Fee { asset, charge }))
+    }
+
+    #[getter]
+    fn asset(&self) -> String {
+        self.0.asset.clone()
+    }
+
+    #[getter]
+    fn flat(&self) -> Option<PyQuantity> {
+        match self.0.charge {
+            Charge::Flat(amount) => Some(PyQuantity(amount)),
+            Charge::Percent(_) => None,
+        }
+    }
+
+    #[getter]
+    fn percent(&self) -> Option<PyQuantity> {
+        match self.0.charge {
+            Charge::Flat(_) => None,
+            Charge::Percent(percent) => Some(PyQuantity(percent)),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match self.0.charge {
+            Charge::Flat(Quantity::Amount(amount)) => {
+                format!("Fee('{}', flat='{}')", self.0.asset, exact(amount))
+            }
+            Charge::Percent(Quantity::Amount(percent)) => {
+                format!("Fee('{}', percent='{}')", self.0.asset, exact(percent))
+            }
+        }
+    }
+}
+
+#[pyclass(name = "Rate")]
+#[derive(Clone)]
+pub struct PyRate(Rate<String>);
+
+#[pymethods]
+impl PyRate {
+    #[new]
+    #[pyo3(signature = (credit = None, debit = None, fee = None))]
+    fn new(
+        credit: Option<BTreeMap<String, Bound<PyAny>>>,
+        debit: Option<BTreeMap<String, Bound<PyAny>>>,
+        fee: Option<PyFee>,
+    ) -> PyResult<Self> {
+        Ok(PyRate(Rate {
+            credit: extract_assets(credit)?,
+            debit: extract_assets(debit)?,
+            fee: fee.map(|PyFee(fee)| fee),
+        }))
+    }
+
+    #[getter]
+    fn credit(&self) -> BTreeMap<String, PyQuantity> {
+        to_dict(&self.0.credit)
+    }
+
+    #[getter]
+    fn debit(&self) -> BTreeMap<String, PyQuantity> {
+        to_dict(&self.0.debit)
+    }
+
+    #[getter]
+    fn fee(&self) -> Option<PyFee> {
+        self.0.fee.clone().map(PyFee)
+    }
+}
+
+#[pyclass(name = "Tranx")]
+pub struct PyTranx {
+    #[pyo3(get)]
+    approved: bool,
+    #[pyo3(get)]
+    buyer: Option<PyAccount>,
+    #[pyo3(get)]
+    seller: Option<PyAccount>,
+    #[pyo3(get)]
+    fee_account: Option<PyAccount>,
+    #[pyo3(get)]
+    deficit: BTreeMap<String, PyQuantity>,
+}
+
+impl From<Tranx<String>> for PyTranx {
+    fn from(tranx: Tranx<String>) -> Self {
+        match tranx {
+            Tranx::Approved(buyer, seller, fee_account) => PyTranx {
+                approved: true,
+                buyer: Some(PyAccount(buyer)),
+                seller: Some(PyAccount(seller)),
+                fee_account: Some(PyAccount(fee_account)),
+                deficit: BTreeMap::new(),
+            },
+            Tranx::Denied(deficit) => PyTranx {
+                approved: false,
+                buyer: None,
+                seller: None,
+                fee_account: None,
+                deficit: to_dict(&deficit),
+            },
+        }
+    }
+}
+
+#[pyclass(name = "AgentReport")]
+#[derive(Clone)]
+pub struct PyAgentReport {
+    #[pyo3(get)]
+    account: String,
+    #[pyo3(get)]
+    lifetime: u64,
+    #[pyo3(get)]
+    alive: bool,
+}
+
+#[pyclass(name = "Report")]
+pub struct PyReport {
+    #[pyo3(get)]
+    ticks: u64,
+    #[pyo3(get)]
+    approved: u64,
+    #[pyo3(get)]
+    denied: u64,
+    #[pyo3(get)]
+    agents: Vec<PyAgentReport>,
+}
+
+impl From<Report> for PyReport {
+    fn from(report: Report) -> Self {
+        PyReport {
+            ticks: report.ticks,
+            approved: report.approved,
+            denied: report.denied,
+            agents: report
+                .agents
+                .into_iter()
+                .map(
+                    |AgentReport {
+                         account,
+                         lifetime,
+                         alive,
+                     }| PyAgentReport {
+                        account,
+                        lifetime,
+                        alive,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+/// An economy definition and simulation runner, as used by the `exgine` CLI.
+#[pyclass(name = "Economy")]
+#[derive(Clone)]
+pub struct PyEconomy(Economy);
+
+#[pymethods]
+impl PyEconomy {
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        Economy::load(path).map(PyEconomy).map_err(error)
+    }
+
+    #[staticmethod]
+    fn parse(source: &str) -> PyResult<Self> {
+        Economy::parse(source).map(PyEconomy).map_err(error)
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    #[getter]
+    fn accounts(&self) -> BTreeMap<String, PyAccount> {
+        self.0
+            .accounts
+            .iter()
+            .map(|(name, account)| (name.clone(), PyAccount(account.clone())))
+            .collect()
+    }
+
+    /// The account every fee is paid into, if any.
+    #[getter]
+    fn treasury(&self) -> Option<String> {
+        self.0.treasury.clone()
+    }
+
+    #[setter]
+    fn set_treasury(&mut self, treasury: Option<String>) -> PyResult<()> {
+        if let Some(treasury) = &treasury {
+            self.0.account(treasury).map_err(error)?;
+        }
+        self.0.treasury = treasury;
+        Ok(())
+    }
+
+    #[getter]
+    fn rates(&self) -> BTreeMap<String, PyRate> {
+        self.0
+            .rates
+            .iter()
+            .map(|(name, rate)| (name.clone(), PyRate(rate.clone())))
+            .collect()
+    }
+
+    fn set_account(&mut self, name: &str, account: &PyAccount) {
+        self.0.accounts.insert(name.to_string(), account.0.clone());
+    }
+
+    fn set_rate(&mut self, name: &str, rate: &PyRate) -> PyResult<()> {
+        self.0.register_rate(name, rate.0.clone()).map_err(error)
+    }
+
+    fn exchange(
+        &mut self,
+        rate: &str,
+        quantity: &Bound<PyAny>,
+        buyer: &str,
+        seller: &str,
+    ) -> PyResult<PyTranx> {
+        let quantity = extract_quantity(quantity)?;
+        let tranx = self
+            .0
+            .exchange(rate, quantity, buyer, seller)
+            .map_err(error)?;
+        Ok(PyTranx::from(tranx))
+    }
+
+    #[pyo3(signature = (ticks = None))]
+    fn run(&mut self, ticks: Option<u64>) -> PyResult<PyReport> {
+        simulation::run(&mut self.0, ticks)
+            .map(PyReport::from)
+            .map_err(error)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "exgine")]
+fn exgine_py(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_class::<PyQuantity>()?;
+    m.add_class::<PyAccount>()?;
+    m.add_class::<PyFee>()?;
+    m.add_class::<PyRate>()?;
+    m.add_class::<PyTranx>()?;
+    m.add_class::<PyEconomy>()?;
+    m.add_class::<PyReport>()?;
+    m.add_class::<PyAgentReport>()?;
+    Ok(())
+}
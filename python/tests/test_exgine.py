@@ -0,0 +1,119 @@
+import os
+import unittest
+from decimal import Decimal
+from fractions import Fraction
+
+from exgine import Account, Economy, Fee, Quantity, Rate
+
+ECONOMY = os.path.join(
+    os.path.dirname(__file__), "..", "..", "cli", "economies", "robot_mission.toml"
+)
+
+
+class QuantityTest(unittest.TestCase):
+    def test_exact_decimal(self):
+        self.assertEqual(Quantity(3).to_decimal(), Decimal(3))
+        self.assertEqual(Quantity("1.5").to_decimal(), Decimal("1.5"))
+        self.assertEqual(Quantity(Decimal("-0.25")).to_decimal(), Decimal("-0.25"))
+        # 0.1 has no exact binary expansion; the decimal is the stored value.
+        tenth = Quantity("0.1")
+        self.assertEqual(tenth.to_decimal(), Decimal("0.1000000000931322574615478515625"))
+        self.assertEqual(tenth.bits, 429496730)
+
+    def test_arithmetic(self):
+        self.assertEqual(Quantity(2) + "0.5", Quantity("2.5"))
+        self.assertEqual(Quantity(2) - 3, -Quantity(1))
+        self.assertLess(Quantity(1), 2)
+
+    def test_invalid(self):
+        with self.assertRaises(ValueError):
+            Quantity("lots")
+
+    def test_hash_matches_numbers(self):
+        for value in (3, -7, Decimal("1.5"), 0.5):
+            self.assertEqual(Quantity(value), value)
+            self.assertEqual(hash(Quantity(value)), hash(value))
+        self.assertEqual(hash(Quantity("-0.25")), hash(Fraction(-1, 4)))
+        self.assertEqual({Quantity(2): "two"}[2], "two")
+        self.assertNotEqual(Quantity(2), "2")
+
+
+class AccountTest(unittest.TestCase):
+    def test_exchange(self):
+        rate = Rate(credit={"Labor": 2}, debit={"Energy": 1})
+        buyer = Account({"Energy": 10})
+        seller = Account({"Labor": 10})
+
+        tranx = Account.exchange(rate, 3, buyer, seller)
+        self.assertTrue(tranx.approved)
+        self.assertEqual(tranx.buyer.quantity("Energy"), 7)
+        self.assertEqual(tranx.buyer.quantity("Labor"), 6)
+        self.assertEqual(tranx.seller.assets(), {"Energy": Quantity(3), "Labor": Quantity(4)})
+
+        tranx = Account.exchange(rate, 11, buyer, seller)
+        self.assertFalse(tranx.approved)
+        self.assertIsNone(tranx.buyer)
+        self.assertEqual(tranx.deficit["Energy"], -1)
+
+    def test_exchange_fee(self):
+        rate = Rate(credit={"Labor": 2}, debit={"Energy": 2}, fee=Fee("Energy", percent=50))
+        self.assertEqual(rate.fee.percent, 50)
+        self.assertIsNone(rate.fee.flat)
+        buyer = Account({"Energy": 10})
+        seller = Account({"Labor": 10})
+
+        tranx = Account.exchange(rate, 2, buyer, seller, Account({"Energy": 1}))
+        self.assertTrue(tranx.approved)
+        self.assertEqual(tranx.buyer.quantity("Energy"), 4)
+        self.assertEqual(tranx.fee_account.assets(), {"Energy": Quantity(3)})
+        self.assertEqual(Account.exchange(rate, 1, buyer, seller).fee_account.quantity("Energy"), 1)
+
+        with self.assertRaises(ValueError):
+            Fee("Energy")
+        with self.assertRaises(ValueError):
+            Fee("Energy", flat=1, percent=5)
+
+    def test_compare(self):
+        self.assertEqual(Account({"Energy": 1}), Account({"Energy": 1}))
+        self.assertNotEqual(Account({"Energy": 1}), Account({"Energy": 2}))
+        self.assertNotEqual(Account({"Energy": 1}), {"Energy": 1})
+        with self.assertRaises(TypeError):
+            Account() < Account()
+
+
+class EconomyTest(unittest.TestCase):
+    def test_parameter_sweep(self):
+        base = Economy.load(ECONOMY)
+        lifetimes = []
+        for battery in (10, 20, 40):
+            economy = base.copy()
+            rate = economy.rates["MissionTimeWithResource"]
+            debit = dict(rate.debit, Battery=battery)
+            economy.set_rate("MissionTimeWithResource", Rate(rate.credit, debit))
+            lifetimes.append(economy.run().agents[0].lifetime)
+        self.assertEqual(lifetimes, [11000, 10500, 10250])
+
+    def test_treasury(self):
+        economy = Economy.load(ECONOMY)
+        rate = Rate({"MissionTime": 1}, {"Trust": 1}, Fee("Trust", flat="0.5"))
+        with self.assertRaises(ValueError):
+            economy.set_rate("Tolled", rate)
+        with self.assertRaises(ValueError):
+            economy.treasury = "nobody"
+        economy.set_account("treasury", Account())
+        economy.treasury = "treasury"
+        economy.set_rate("Tolled", rate)
+        self.assertEqual(economy.rates["Tolled"].fee.flat, Quantity("0.5"))
+
+        self.assertTrue(economy.exchange("Tolled", 2, "agent", "mission").approved)
+        self.assertEqual(economy.accounts["treasury"].quantity("Trust"), Quantity("0.5"))
+        self.assertEqual(economy.accounts["agent"].quantity("Trust"), Quantity("9997.5"))
+
+    def test_unknown_rate(self):
+        economy = Economy.load(ECONOMY)
+        with self.assertRaises(ValueError):
+            economy.exchange("missing", 1, "agent", "mission")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,7 +1,9 @@
 use crate::asset::*;
+#[cfg(debug_assertions)]
+use crate::invariant;
 use crate::rate::*;
-use std::collections::HashMap;
-use std::ops;
+use crate::map::Map;
+use core::ops;
 pub use substrate_fixed::types::I32F32;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
@@ -13,67 +15,523 @@ pub fn fixed_amount(amount: i32) -> Quantity {
     Quantity::Amount(I32F32::from(amount))
 }
 
+/// Balances by asset, plus optional credit lines: how far below zero each
+/// asset may go when the account pays for an exchange. Held amounts are part
+/// of the balance but cannot be spent until released or captured.
 #[derive(Debug, Clone)]
-pub struct Account<TAsset: Asset>(HashMap<TAsset, Quantity>);
+pub struct Account<TAsset: Asset> {
+    pub(crate) balances: Map<TAsset, Quantity>,
+    pub(crate) credit: Map<TAsset, Quantity>,
+    pub(crate) held: Map<TAsset, Quantity>,
+}
 
+#[allow(clippy::large_enum_variant)]
 pub enum Tranx<TAsset: Asset> {
-    Approved(Account<TAsset>, Account<TAsset>),
-    Denied(HashMap<TAsset, Quantity>),
+    Approved(Account<TAsset>, Account<TAsset>, Account<TAsset>),
+    /// How far each short asset would end up beyond the credit limit of the
+    /// side paying it, after what that side holds: the buyer for the debit and
+    /// fee, the seller for the credit.
+    Denied(Map<TAsset, Quantity>),
+}
+
+/// A preview of `Account::exchange_with_fee`. Balances cover only the assets
+/// the exchange touches and match what the exchange would produce exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quote<TAsset: Asset> {
+    pub credit: Map<TAsset, Quantity>,
+    pub debit: Map<TAsset, Quantity>,
+    pub fees: Map<TAsset, Quantity>,
+    pub buyer: Map<TAsset, Quantity>,
+    pub seller: Map<TAsset, Quantity>,
+    pub deficit: Map<TAsset, Quantity>,
 }
 
-impl<TAsset: Asset> From<HashMap<TAsset, Quantity>> for Account<TAsset> {
-    fn from(assets: HashMap<TAsset, Quantity>) -> Self {
-        Account(assets)
+impl<TAsset: Asset> Quote<TAsset> {
+    pub fn approved(&self) -> bool {
+        self.deficit.is_empty()
+    }
+}
+
+impl<TAsset: Asset> Default for Account<TAsset> {
+    fn default() -> Self {
+        Account::from(hashmap![])
+    }
+}
+
+impl<TAsset: Asset> From<Map<TAsset, Quantity>> for Account<TAsset> {
+    fn from(assets: Map<TAsset, Quantity>) -> Self {
+        Account {
+            balances: assets,
+            credit: hashmap![],
+            held: hashmap![],
+        }
     }
 }
 
 impl<TAsset: Asset> Account<TAsset> {
+    /// Total balance of `asset`, held amounts included.
     pub fn quantity(&self, asset: &TAsset) -> Quantity {
-        match self.0.get(asset) {
-            Some(quantity) => quantity.clone(),
+        match self.balances.get(asset) {
+            Some(quantity) => *quantity,
+            None => fixed_amount(0),
+        }
+    }
+
+    /// Balance of `asset` left to spend: the total minus what is held.
+    pub fn available(&self, asset: &TAsset) -> Quantity {
+        let (Quantity::Amount(total), Quantity::Amount(held)) =
+            (self.quantity(asset), self.held(asset));
+        Quantity::Amount(total - held)
+    }
+
+    pub fn held(&self, asset: &TAsset) -> Quantity {
+        match self.held.get(asset) {
+            Some(quantity) => *quantity,
             None => fixed_amount(0),
         }
     }
 
+    pub fn holds(&self) -> &Map<TAsset, Quantity> {
+        &self.held
+    }
+
+    /// Reserves `amount` of `asset` so no exchange can spend it. Denied, with
+    /// how far beyond the credit limit the available balance would fall, if
+    /// the account cannot cover it, or with `amount` itself if it is not
+    /// positive.
+    pub fn hold(&mut self, asset: &TAsset, amount: Quantity) -> Result<(), Map<TAsset, Quantity>> {
+        let (Quantity::Amount(total), Quantity::Amount(amount)) = (self.quantity(asset), amount);
+        Account::positive(asset, amount)?;
+        if let Some(beyond) = self.beyond_credit(asset, total - amount) {
+            return Err(hashmap![asset.clone() => Quantity::Amount(beyond)]);
+        }
+        self.adjust_held(asset, amount);
+        Ok(())
+    }
+
+    /// Returns `amount` of a hold on `asset` to the available balance. Denied,
+    /// with the missing amount, if less than `amount` is held, or with
+    /// `amount` itself if it is not positive.
+    pub fn release(
+        &mut self,
+        asset: &TAsset,
+        amount: Quantity,
+    ) -> Result<(), Map<TAsset, Quantity>> {
+        let Quantity::Amount(amount) = amount;
+        self.unhold(asset, amount)?;
+        self.adjust_held(asset, -amount);
+        Ok(())
+    }
+
+    /// Pays `amount` of a hold on `asset` into `payee`. Denied, with the
+    /// missing amount, if less than `amount` is held, or with `amount` itself
+    /// if it is not positive.
+    pub fn capture(
+        &mut self,
+        asset: &TAsset,
+        amount: Quantity,
+        payee: &mut Account<TAsset>,
+    ) -> Result<(), Map<TAsset, Quantity>> {
+        let Quantity::Amount(amount) = amount;
+        self.unhold(asset, amount)?;
+        self.adjust_held(asset, -amount);
+        self.adjust(asset, -amount);
+        payee.adjust(asset, amount);
+        Ok(())
+    }
+
+    fn held_amount(&self, asset: &TAsset) -> I32F32 {
+        let Quantity::Amount(held) = self.held(asset);
+        held
+    }
+
+    fn positive(asset: &TAsset, amount: I32F32) -> Result<(), Map<TAsset, Quantity>> {
+        if amount > 0 {
+            Ok(())
+        } else {
+            Err(hashmap![asset.clone() => Quantity::Amount(amount)])
+        }
+    }
+
+    fn unhold(&self, asset: &TAsset, amount: I32F32) -> Result<(), Map<TAsset, Quantity>> {
+        Account::positive(asset, amount)?;
+        let left = self.held_amount(asset) - amount;
+        if left < 0 {
+            Err(hashmap![asset.clone() => Quantity::Amount(left)])
+        } else {
+            Ok(())
+        }
+    }
+
+    fn adjust_held(&mut self, asset: &TAsset, delta: I32F32) {
+        let held = self.held_amount(asset) + delta;
+        if held == 0 {
+            self.held.remove(asset);
+        } else {
+            self.held.insert(asset.clone(), Quantity::Amount(held));
+        }
+    }
+
+    /// Lets `asset` go as far as `limit` below zero when this account pays for
+    /// an exchange. A zero or negative limit removes the credit line.
+    pub fn with_credit(mut self, asset: TAsset, limit: Quantity) -> Self {
+        if limit <= fixed_amount(0) {
+            self.credit.remove(&asset);
+        } else {
+            self.credit.insert(asset, limit);
+        }
+        self
+    }
+
+    /// The credit limit on `asset`, zero without a credit line.
+    pub fn credit(&self, asset: &TAsset) -> Quantity {
+        match self.credit.get(asset) {
+            Some(limit) => *limit,
+            None => fixed_amount(0),
+        }
+    }
+
+    pub fn credit_lines(&self) -> &Map<TAsset, Quantity> {
+        &self.credit
+    }
+
+    // How far `balance` of `asset`, less what this account holds, is beyond
+    // its credit limit, if it is.
+    fn beyond_credit(&self, asset: &TAsset, balance: I32F32) -> Option<I32F32> {
+        let Quantity::Amount(limit) = self.credit(asset);
+        let headroom = balance - self.held_amount(asset) + limit;
+        if headroom < 0 {
+            Some(headroom)
+        } else {
+            None
+        }
+    }
+
     pub fn exchange(
         rate: &Rate<TAsset>,
         quantity: Quantity,
         buyer: &Account<TAsset>,
         seller: &Account<TAsset>,
     ) -> Tranx<TAsset> {
-        let credit = &Account(rate.credit.clone()) * quantity;
-        let debit = &Account(rate.debit.clone()) * quantity;
-        let (buyer, seller) = (&(buyer - &debit) + &credit, &(seller - &credit) + &debit);
-        let mut success = true;
-        let mut deficit = hashmap![];
-        {
-            let buyer = buyer.assets();
-            let debit = debit.assets();
-            for asset in debit.keys() {
-                match buyer.get(asset) {
-                    Some(Quantity::Amount(quantity)) if *quantity < 0 => {
-                        success = false;
-                        deficit.insert(asset.clone(), Quantity::Amount(*quantity));
-                    }
-                    _ => (),
-                }
+        Account::exchange_with_fee(rate, quantity, buyer, seller, &Account::default())
+    }
+
+    /// Like `exchange`, but the buyer also pays the rate's fee, if any, into
+    /// `fee_account`. The fee is part of the same transaction: if the buyer
+    /// cannot cover it, the whole exchange is denied. The seller is held to
+    /// its own credit lines on the assets it delivers; when the buyer and
+    /// seller are both short of one asset, the buyer's deficit is reported.
+    /// A negative fee would pay the buyer, so it is denied and reported as is.
+    pub fn exchange_with_fee(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        fee_account: &Account<TAsset>,
+    ) -> Tranx<TAsset> {
+        let credit = &Account::from(rate.credit.clone()) * quantity;
+        let debit = &Account::from(rate.debit.clone()) * quantity;
+        let fee = match &rate.fee {
+            Some(fee) => Account::from(hashmap![
+                fee.asset.clone() => fee.amount(debit.quantity(&fee.asset))
+            ]),
+            None => Account::default(),
+        };
+        #[cfg(debug_assertions)]
+        let before = [buyer, seller, fee_account];
+        let (buyer, seller) = (
+            &(&(buyer - &debit) - &fee) + &credit,
+            &(seller - &credit) + &debit,
+        );
+        let fee_account = fee_account + &fee;
+        let mut deficit = buyer.shortfall(debit.assets().keys().chain(fee.assets().keys()));
+        for (asset, short) in seller.shortfall(credit.assets().keys()) {
+            deficit.entry(asset).or_insert(short);
+        }
+        for (asset, charged) in fee.assets() {
+            if *charged < fixed_amount(0) {
+                deficit.insert(asset.clone(), *charged);
             }
         }
-        if success {
-            Tranx::Approved(buyer, seller)
+        if deficit.is_empty() {
+            #[cfg(debug_assertions)]
+            invariant::verify("Account::exchange", before, [&buyer, &seller, &fee_account]);
+            Tranx::Approved(buyer, seller, fee_account)
         } else {
             Tranx::Denied(deficit)
         }
     }
 
-    pub fn assets(&self) -> &HashMap<TAsset, Quantity> {
-        let Account(assets) = self;
-        assets
+    /// `exchange_with_fee` applied to the accounts themselves. An approved
+    /// exchange only touches the assets in the rate and fee, without cloning or
+    /// rebuilding any account. A denied one leaves every account untouched and
+    /// returns the deficit.
+    pub fn exchange_in_place(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &mut Account<TAsset>,
+        seller: &mut Account<TAsset>,
+        fee_account: &mut Account<TAsset>,
+    ) -> Result<(), Map<TAsset, Quantity>> {
+        if !Account::can_afford(rate, quantity, buyer, seller) {
+            return Err(Account::quote(rate, quantity, buyer, seller).deficit);
+        }
+        #[cfg(debug_assertions)]
+        let before = invariant::total([&*buyer, &*seller, &*fee_account]);
+        let Quantity::Amount(q) = quantity;
+        for (asset, Quantity::Amount(amount)) in &rate.debit {
+            buyer.adjust(asset, -(*amount * q));
+            seller.adjust(asset, *amount * q);
+        }
+        if let Some((asset, amount)) = Account::fee(rate, q) {
+            buyer.adjust(asset, -amount);
+            fee_account.adjust(asset, amount);
+        }
+        for (asset, Quantity::Amount(amount)) in &rate.credit {
+            buyer.adjust(asset, *amount * q);
+            seller.adjust(asset, -(*amount * q));
+        }
+        #[cfg(debug_assertions)]
+        invariant::verify(
+            "Account::exchange_in_place",
+            [&before],
+            [&*buyer, &*seller, &*fee_account],
+        );
+        Ok(())
+    }
+
+    fn adjust(&mut self, asset: &TAsset, delta: I32F32) {
+        match self.balances.get_mut(asset) {
+            Some(Quantity::Amount(quantity)) => *quantity += delta,
+            None => {
+                self.balances.insert(asset.clone(), Quantity::Amount(delta));
+            }
+        }
+    }
+
+    /// Applies `recipe` `quantity` times to `account`: consumes the inputs and
+    /// produces the outputs. Denied, like `exchange`, with how far each short
+    /// input would end up beyond the credit limit, or with `quantity` for every
+    /// asset of the recipe if it is not positive. Unlike an exchange, this
+    /// creates and destroys assets.
+    pub fn produce(
+        recipe: &Recipe<TAsset>,
+        quantity: Quantity,
+        account: &Account<TAsset>,
+    ) -> Result<Account<TAsset>, Map<TAsset, Quantity>> {
+        if quantity <= fixed_amount(0) {
+            return Err(recipe
+                .inputs
+                .keys()
+                .chain(recipe.outputs.keys())
+                .map(|asset| (asset.clone(), quantity))
+                .collect());
+        }
+        let inputs = &Account::from(recipe.inputs.clone()) * quantity;
+        let outputs = &Account::from(recipe.outputs.clone()) * quantity;
+        let account = &(account - &inputs) + &outputs;
+        let deficit = account.shortfall(inputs.assets().keys());
+        if deficit.is_empty() {
+            Ok(account)
+        } else {
+            Err(deficit)
+        }
+    }
+
+    /// Previews an exchange without executing it.
+    pub fn quote(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+    ) -> Quote<TAsset> {
+        let Quantity::Amount(q) = quantity;
+        let scale = |side: &Map<TAsset, Quantity>| -> Map<TAsset, Quantity> {
+            side.iter()
+                .map(|(asset, Quantity::Amount(amount))| {
+                    (asset.clone(), Quantity::Amount(*amount * q))
+                })
+                .collect()
+        };
+        let fee = Account::fee(rate, q);
+        let mut quote = Quote {
+            credit: scale(&rate.credit),
+            debit: scale(&rate.debit),
+            fees: fee
+                .iter()
+                .map(|(asset, amount)| ((*asset).clone(), Quantity::Amount(*amount)))
+                .collect(),
+            buyer: hashmap![],
+            seller: hashmap![],
+            deficit: hashmap![],
+        };
+        let touched = rate
+            .credit
+            .keys()
+            .chain(rate.debit.keys())
+            .chain(fee.map(|(asset, _)| asset));
+        for asset in touched {
+            let (buyer_after, seller_after) = Account::settle(rate, q, fee, buyer, seller, asset);
+            quote
+                .buyer
+                .insert(asset.clone(), Quantity::Amount(buyer_after));
+            quote
+                .seller
+                .insert(asset.clone(), Quantity::Amount(seller_after));
+            let settled = (buyer_after, seller_after);
+            if let Some(beyond) = Account::beyond(rate, fee, buyer, seller, asset, settled) {
+                quote
+                    .deficit
+                    .insert(asset.clone(), Quantity::Amount(beyond));
+            }
+        }
+        quote
+    }
+
+    /// Whether `exchange` would approve, without allocating.
+    pub fn can_afford(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+    ) -> bool {
+        let Quantity::Amount(q) = quantity;
+        let fee = Account::fee(rate, q);
+        rate.credit
+            .keys()
+            .chain(rate.debit.keys())
+            .chain(fee.map(|(asset, _)| asset))
+            .all(|asset| {
+                let settled = Account::settle(rate, q, fee, buyer, seller, asset);
+                Account::beyond(rate, fee, buyer, seller, asset, settled).is_none()
+            })
+    }
+
+    /// How far `asset` ends up beyond credit, for the buyer if it pays that
+    /// asset, else for the seller if it delivers it. A negative fee in `asset`
+    /// is reported as is.
+    fn beyond(
+        rate: &Rate<TAsset>,
+        fee: Option<(&TAsset, I32F32)>,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        asset: &TAsset,
+        (buyer_after, seller_after): (I32F32, I32F32),
+    ) -> Option<I32F32> {
+        let fee = fee
+            .filter(|(fee, _)| *fee == asset)
+            .map(|(_, amount)| amount);
+        let paid = rate.debit.contains_key(asset) || fee.is_some();
+        let delivered = rate.credit.contains_key(asset);
+        fee.filter(|amount| *amount < 0)
+            .or_else(|| {
+                paid.then(|| buyer.beyond_credit(asset, buyer_after))
+                    .flatten()
+            })
+            .or_else(|| {
+                delivered
+                    .then(|| seller.beyond_credit(asset, seller_after))
+                    .flatten()
+            })
+    }
+
+    fn fee(rate: &Rate<TAsset>, q: I32F32) -> Option<(&TAsset, I32F32)> {
+        rate.fee.as_ref().map(|fee| {
+            let debited = match rate.debit.get(&fee.asset) {
+                Some(Quantity::Amount(amount)) => Quantity::Amount(*amount * q),
+                None => fixed_amount(0),
+            };
+            let Quantity::Amount(amount) = fee.amount(debited);
+            (&fee.asset, amount)
+        })
+    }
+
+    /// Buyer and seller balances of `asset` after an exchange, in the same
+    /// order of operations as `exchange_with_fee` so they match it exactly.
+    fn settle(
+        rate: &Rate<TAsset>,
+        q: I32F32,
+        fee: Option<(&TAsset, I32F32)>,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        asset: &TAsset,
+    ) -> (I32F32, I32F32) {
+        let zero = I32F32::from(0);
+        let scaled = |side: &Map<TAsset, Quantity>| match side.get(asset) {
+            Some(Quantity::Amount(amount)) => *amount * q,
+            None => zero,
+        };
+        let (credit, debit) = (scaled(&rate.credit), scaled(&rate.debit));
+        let fee = match fee {
+            Some((fee_asset, amount)) if fee_asset == asset => amount,
+            _ => zero,
+        };
+        let (Quantity::Amount(buyer), Quantity::Amount(seller)) =
+            (buyer.quantity(asset), seller.quantity(asset));
+        (buyer - debit - fee + credit, seller - credit + debit)
+    }
+
+    /// Executes the largest quantity, up to `quantity`, that the buyer can pay
+    /// for (fees included) and the seller can deliver, down to the last
+    /// fixed-point bit. Assumes the rate's credit and debit are non-negative so
+    /// affordability only shrinks as the quantity grows. Returns the filled
+    /// quantity, or zero and the denial for the full quantity if not even the
+    /// smallest fill is possible.
+    pub fn fill_max(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        fee_account: &Account<TAsset>,
+    ) -> (Quantity, Tranx<TAsset>) {
+        let attempt = |bits: i64| {
+            let quantity = Quantity::Amount(I32F32::from_bits(bits));
+            Account::exchange_with_fee(rate, quantity, buyer, seller, fee_account)
+        };
+        let Quantity::Amount(requested) = quantity;
+        let full = attempt(requested.to_bits());
+        if let Tranx::Approved(..) = full {
+            return (quantity, full);
+        }
+        let (mut lo, mut hi) = (0, requested.to_bits());
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            match attempt(mid) {
+                Tranx::Approved(..) => lo = mid,
+                Tranx::Denied(_) => hi = mid,
+            }
+        }
+        if lo == 0 {
+            (fixed_amount(0), full)
+        } else {
+            (Quantity::Amount(I32F32::from_bits(lo)), attempt(lo))
+        }
+    }
+
+    /// How far each of `assets` is beyond this account's credit limit, after
+    /// holds, for those that are.
+    pub(crate) fn shortfall<'a, I>(&self, assets: I) -> Map<TAsset, Quantity>
+    where
+        TAsset: 'a,
+        I: IntoIterator<Item = &'a TAsset>,
+    {
+        let mut shortfall = hashmap![];
+        for asset in assets {
+            let Quantity::Amount(quantity) = self.quantity(asset);
+            if let Some(beyond) = self.beyond_credit(asset, quantity) {
+                shortfall.insert(asset.clone(), Quantity::Amount(beyond));
+            }
+        }
+        shortfall
+    }
+
+    pub fn assets(&self) -> &Map<TAsset, Quantity> {
+        &self.balances
     }
 
     fn prime(&mut self, rhs: &Account<TAsset>) {
-        let Account(lhs) = self;
-        let Account(rhs) = rhs;
+        let lhs = &mut self.balances;
+        let rhs = &rhs.balances;
         for rhs_key in rhs.keys() {
             if !lhs.contains_key(rhs_key) {
                 lhs.insert(rhs_key.clone(), fixed_amount(0));
@@ -81,6 +539,8 @@ impl<TAsset: Asset> Account<TAsset> {
         }
     }
 
+    /// Applies `op` asset by asset. The result keeps the credit lines and holds
+    /// of `lhs`.
     fn op<F>(lhs: &Account<TAsset>, rhs: &Account<TAsset>, op: F) -> Account<TAsset>
     where
         F: Fn(&Quantity, &Quantity) -> Quantity,
@@ -90,27 +550,28 @@ impl<TAsset: Asset> Account<TAsset> {
         let mut rhs = rhs.clone();
         lhs.prime(&rhs);
         rhs.prime(&lhs);
-        let Account(lhs) = lhs;
-        let Account(rhs) = rhs;
-        for key in lhs.keys() {
-            let lhs_quantity = lhs.get(key).unwrap();
-            let rhs_quantity = rhs.get(key).unwrap();
+        for key in lhs.balances.keys() {
+            let lhs_quantity = lhs.balances.get(key).unwrap();
+            let rhs_quantity = rhs.balances.get(key).unwrap();
             let quantity = op(lhs_quantity, rhs_quantity);
-            acc.insert(key.clone(), quantity.clone());
+            acc.insert(key.clone(), quantity);
+        }
+        Account {
+            balances: acc,
+            credit: lhs.credit,
+            held: lhs.held,
         }
-        Account(acc)
     }
 }
 
+/// Compares balances only; credit lines and holds are ignored.
 impl<TAsset: Asset> PartialEq for Account<TAsset> {
     fn eq(&self, rhs: &Account<TAsset>) -> bool {
         let mut lhs = self.clone();
         let mut rhs = rhs.clone();
         lhs.prime(&rhs);
         rhs.prime(&lhs);
-        let Account(lhs) = lhs;
-        let Account(rhs) = rhs;
-        lhs == rhs
+        lhs.balances == rhs.balances
     }
 }
 
@@ -134,19 +595,38 @@ impl<TAsset: Asset> ops::Sub<&Account<TAsset>> for &Account<TAsset> {
     }
 }
 
+impl<TAsset: Asset> ops::AddAssign<&Account<TAsset>> for Account<TAsset> {
+    fn add_assign(&mut self, rhs: &Account<TAsset>) {
+        for (asset, Quantity::Amount(quantity)) in rhs.assets() {
+            self.adjust(asset, *quantity);
+        }
+    }
+}
+
+impl<TAsset: Asset> ops::SubAssign<&Account<TAsset>> for Account<TAsset> {
+    fn sub_assign(&mut self, rhs: &Account<TAsset>) {
+        for (asset, Quantity::Amount(quantity)) in rhs.assets() {
+            self.adjust(asset, -*quantity);
+        }
+    }
+}
+
 impl<TAsset: Asset> ops::Mul<Quantity> for &Account<TAsset> {
     type Output = Account<TAsset>;
 
     fn mul(self, rhs: Quantity) -> Account<TAsset> {
-        let Account(lhs) = self;
-        let keys = lhs.keys();
-        let mut lhs = lhs.clone();
+        let keys = self.balances.keys();
+        let mut lhs = self.balances.clone();
         let Quantity::Amount(rhs_quantity) = rhs;
         for key in keys {
             let q = lhs.entry(key.clone()).or_insert(fixed_amount(0));
             let Quantity::Amount(lhs_quantity) = *q;
             *q = Quantity::Amount(lhs_quantity * rhs_quantity);
         }
-        Account(lhs)
+        Account {
+            balances: lhs,
+            credit: self.credit.clone(),
+            held: self.held.clone(),
+        }
     }
 }
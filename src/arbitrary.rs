@@ -0,0 +1,33 @@
+use crate::account::{Account, Quantity, I32F32};
+use crate::asset::Asset;
+use crate::map::Map;
+use proptest::{collection, prelude::*};
+
+// Generated amounts stay within ±2^20, so sums and small integer multiples of
+// them cannot overflow I32F32.
+const BOUND: i64 = 1 << 52;
+
+impl Arbitrary for Quantity {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Quantity>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (-BOUND..BOUND)
+            .prop_map(|bits| Quantity::Amount(I32F32::from_bits(bits)))
+            .boxed()
+    }
+}
+
+impl<TAsset> Arbitrary for Account<TAsset>
+where
+    TAsset: Asset + Arbitrary + 'static,
+{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Account<TAsset>>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        collection::vec(any::<(TAsset, Quantity)>(), 0..8)
+            .prop_map(|assets| Account::from(assets.into_iter().collect::<Map<_, _>>()))
+            .boxed()
+    }
+}
@@ -1,3 +1,8 @@
-use std::hash::Hash;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 
-pub trait Asset: Eq + Hash + Clone {}
+/// Assets are both hashable and ordered whatever the features, so enabling
+/// `ordered` never changes which types qualify.
+pub trait Asset: Eq + core::hash::Hash + Ord + Clone {}
+
+impl Asset for String {}
@@ -0,0 +1,196 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::account::{Account, Quantity};
+use crate::asset::Asset;
+#[cfg(debug_assertions)]
+use crate::invariant;
+use crate::map::Map;
+use crate::rate::Rate;
+use core::{iter, mem};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// An exchange between accounts given by their index in a registry.
+#[derive(Debug, Clone)]
+pub struct Order<'a, TAsset: Asset> {
+    pub rate: &'a Rate<TAsset>,
+    pub quantity: Quantity,
+    pub buyer: usize,
+    pub seller: usize,
+    pub fee_account: usize,
+}
+
+impl<'a, TAsset: Asset> Order<'a, TAsset> {
+    /// The accounts this order reads or writes. The fee account only counts
+    /// when the rate charges a fee.
+    fn touches(&self) -> impl Iterator<Item = usize> {
+        let fee_account = self.rate.fee.as_ref().map(|_| self.fee_account);
+        iter::once(self.buyer)
+            .chain(iter::once(self.seller))
+            .chain(fee_account)
+    }
+}
+
+/// A batch of exchanges, partitioned into waves of orders that touch disjoint
+/// accounts. Each order lands in the first wave after every earlier order that
+/// shares an account with it, so running the waves one after another gives
+/// the same balances and results as running the orders in sequence.
+#[derive(Debug, Clone)]
+pub struct Batch<'a, TAsset: Asset> {
+    orders: Vec<Order<'a, TAsset>>,
+    waves: Vec<Vec<usize>>,
+    // First wave each account is free in.
+    free: Vec<usize>,
+}
+
+impl<'a, TAsset: Asset> Default for Batch<'a, TAsset> {
+    fn default() -> Self {
+        Batch {
+            orders: Vec::new(),
+            waves: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<'a, TAsset: Asset> Batch<'a, TAsset> {
+    pub fn new() -> Self {
+        Batch::default()
+    }
+
+    /// Appends an order to the batch.
+    ///
+    /// Panics if the order's buyer, seller and fee account are not distinct.
+    pub fn push(&mut self, order: Order<'a, TAsset>) {
+        let touched: Vec<usize> = order.touches().collect();
+        assert!(
+            touched
+                .iter()
+                .enumerate()
+                .all(|(i, account)| !touched[..i].contains(account)),
+            "an order's buyer, seller and fee account must be distinct"
+        );
+        let wave = touched
+            .iter()
+            .map(|&account| self.free.get(account).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        for &account in &touched {
+            if self.free.len() <= account {
+                self.free.resize(account + 1, 0);
+            }
+            self.free[account] = wave + 1;
+        }
+        if self.waves.len() <= wave {
+            self.waves.push(Vec::new());
+        }
+        self.waves[wave].push(self.orders.len());
+        self.orders.push(order);
+    }
+
+    pub fn orders(&self) -> &[Order<'a, TAsset>] {
+        &self.orders
+    }
+
+    /// Order indices grouped by wave. Orders within a wave touch disjoint
+    /// accounts.
+    pub fn waves(&self) -> &[Vec<usize>] {
+        &self.waves
+    }
+
+    /// Runs every order in sequence with `Account::exchange_in_place`,
+    /// returning each order's result in order.
+    ///
+    /// Panics if an order refers to an account outside `accounts`.
+    pub fn execute(
+        &self,
+        accounts: &mut [Account<TAsset>],
+    ) -> Vec<Result<(), Map<TAsset, Quantity>>> {
+        #[cfg(debug_assertions)]
+        let before = invariant::total(accounts.iter());
+        let results = self
+            .orders
+            .iter()
+            .map(|order| {
+                let mut parties = take(order, accounts);
+                let result = apply(order, &mut parties);
+                restore(order, accounts, parties);
+                result
+            })
+            .collect();
+        #[cfg(debug_assertions)]
+        invariant::verify("Batch::execute", [&before], accounts.iter());
+        results
+    }
+
+    /// Like `execute`, but runs the orders of each wave in parallel. Balances
+    /// and results are identical to `execute`.
+    #[cfg(feature = "parallel")]
+    pub fn execute_parallel(
+        &self,
+        accounts: &mut [Account<TAsset>],
+    ) -> Vec<Result<(), Map<TAsset, Quantity>>>
+    where
+        TAsset: Send + Sync,
+    {
+        #[cfg(debug_assertions)]
+        let before = invariant::total(accounts.iter());
+        let mut results = Vec::new();
+        results.resize(self.orders.len(), Ok(()));
+        for wave in &self.waves {
+            let mut parties: Vec<_> = wave
+                .iter()
+                .map(|&index| (index, take(&self.orders[index], accounts)))
+                .collect();
+            let applied: Vec<_> = parties
+                .par_iter_mut()
+                .map(|(index, parties)| apply(&self.orders[*index], parties))
+                .collect();
+            for ((index, parties), result) in parties.into_iter().zip(applied) {
+                restore(&self.orders[index], accounts, parties);
+                results[index] = result;
+            }
+        }
+        #[cfg(debug_assertions)]
+        invariant::verify("Batch::execute_parallel", [&before], accounts.iter());
+        results
+    }
+}
+
+// Moves the order's buyer, seller and fee account out of the registry. The fee
+// account stays put, and a blank one stands in, when the rate has no fee.
+fn take<TAsset: Asset>(
+    order: &Order<TAsset>,
+    accounts: &mut [Account<TAsset>],
+) -> [Account<TAsset>; 3] {
+    let fee_account = match order.rate.fee {
+        Some(_) => mem::take(&mut accounts[order.fee_account]),
+        None => Account::default(),
+    };
+    [
+        mem::take(&mut accounts[order.buyer]),
+        mem::take(&mut accounts[order.seller]),
+        fee_account,
+    ]
+}
+
+fn restore<TAsset: Asset>(
+    order: &Order<TAsset>,
+    accounts: &mut [Account<TAsset>],
+    [buyer, seller, fee_account]: [Account<TAsset>; 3],
+) {
+    accounts[order.buyer] = buyer;
+    accounts[order.seller] = seller;
+    if order.rate.fee.is_some() {
+        accounts[order.fee_account] = fee_account;
+    }
+}
+
+fn apply<TAsset: Asset>(
+    order: &Order<TAsset>,
+    [buyer, seller, fee_account]: &mut [Account<TAsset>; 3],
+) -> Result<(), Map<TAsset, Quantity>> {
+    Account::exchange_in_place(order.rate, order.quantity, buyer, seller, fee_account)
+}
@@ -0,0 +1,329 @@
+//! C API over opaque handles. Every handle returned by a `_new` function must
+//! be released with the matching `_free`, pointers must be valid (or null where
+//! noted) and strings NUL-terminated UTF-8.
+
+#![allow(clippy::missing_safety_doc)]
+
+use crate::account::{fixed_amount, Account, Quantity, Tranx, I32F32};
+use crate::map::Map;
+use crate::rate::{Charge, Fee, Rate};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Raw bits of an I32F32 fixed-point quantity: the value times 2^32.
+pub type ExgineQuantity = i64;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExgineStatus {
+    Ok = 0,
+    Denied = 1,
+    InvalidArgument = 2,
+    UnknownRate = 3,
+    /// The engine panicked, for instance on fixed-point overflow in a debug
+    /// build. The accounts are left untouched.
+    Panicked = 4,
+}
+
+/// How `exgine_rate_set_fee` charges the buyer: a fixed amount per exchange,
+/// or a percentage of what it is debited in the fee asset.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExgineCharge {
+    Flat = 0,
+    Percent = 1,
+}
+
+pub struct ExgineAccount(Account<String>);
+
+pub struct ExgineRate(Rate<String>);
+
+pub struct ExgineMarket(Map<String, Rate<String>>);
+
+pub struct ExgineDenial(Vec<(CString, Quantity)>);
+
+fn quantity(quantity: ExgineQuantity) -> Quantity {
+    Quantity::Amount(I32F32::from_bits(quantity))
+}
+
+fn bits(Quantity::Amount(amount): Quantity) -> ExgineQuantity {
+    amount.to_bits()
+}
+
+unsafe fn string(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok().map(str::to_string)
+}
+
+unsafe fn set(assets: &mut Map<String, Quantity>, asset: *const c_char, q: ExgineQuantity) -> ExgineStatus {
+    match string(asset) {
+        Some(asset) => {
+            assets.insert(asset, quantity(q));
+            ExgineStatus::Ok
+        }
+        None => ExgineStatus::InvalidArgument,
+    }
+}
+
+unsafe fn free<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn exgine_quantity_from_int(amount: i32) -> ExgineQuantity {
+    bits(fixed_amount(amount))
+}
+
+/// Writes `amount` to `*quantity`. Fails, leaving it untouched, if `amount` is
+/// NaN or out of range, or `quantity` is null.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_quantity_from_double(
+    amount: f64,
+    quantity: *mut ExgineQuantity,
+) -> ExgineStatus {
+    match (I32F32::checked_from_num(amount), quantity.as_mut()) {
+        (Some(amount), Some(quantity)) => {
+            *quantity = bits(Quantity::Amount(amount));
+            ExgineStatus::Ok
+        }
+        _ => ExgineStatus::InvalidArgument,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn exgine_quantity_to_double(quantity: ExgineQuantity) -> f64 {
+    I32F32::from_bits(quantity).to_num::<f64>()
+}
+
+#[no_mangle]
+pub extern "C" fn exgine_account_new() -> *mut ExgineAccount {
+    Box::into_raw(Box::new(ExgineAccount(Account::default())))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_account_free(account: *mut ExgineAccount) {
+    free(account)
+}
+
+/// Returns 0 for missing assets and for a null account or asset.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_account_quantity(
+    account: *const ExgineAccount,
+    asset: *const c_char,
+) -> ExgineQuantity {
+    match (account.as_ref(), string(asset)) {
+        (Some(account), Some(asset)) => bits(account.0.quantity(&asset)),
+        _ => 0,
+    }
+}
+
+/// Sets one balance, leaving the rest of the account as it is.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_account_set_quantity(
+    account: *mut ExgineAccount,
+    asset: *const c_char,
+    quantity: ExgineQuantity,
+) -> ExgineStatus {
+    match account.as_mut() {
+        Some(account) => set(&mut account.0.balances, asset, quantity),
+        None => ExgineStatus::InvalidArgument,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn exgine_rate_new() -> *mut ExgineRate {
+    Box::into_raw(Box::new(ExgineRate(Rate::default())))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_rate_free(rate: *mut ExgineRate) {
+    free(rate)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_rate_set_credit(
+    rate: *mut ExgineRate,
+    asset: *const c_char,
+    quantity: ExgineQuantity,
+) -> ExgineStatus {
+    match rate.as_mut() {
+        Some(rate) => set(&mut rate.0.credit, asset, quantity),
+        None => ExgineStatus::InvalidArgument,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_rate_set_debit(
+    rate: *mut ExgineRate,
+    asset: *const c_char,
+    quantity: ExgineQuantity,
+) -> ExgineStatus {
+    match rate.as_mut() {
+        Some(rate) => set(&mut rate.0.debit, asset, quantity),
+        None => ExgineStatus::InvalidArgument,
+    }
+}
+
+/// Charges the buyer of every exchange at `rate` a fee in `asset`, replacing
+/// any fee already set.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_rate_set_fee(
+    rate: *mut ExgineRate,
+    asset: *const c_char,
+    charge: ExgineCharge,
+    quantity: ExgineQuantity,
+) -> ExgineStatus {
+    match (rate.as_mut(), string(asset)) {
+        (Some(rate), Some(asset)) => {
+            let charge = match charge {
+                ExgineCharge::Flat => Charge::Flat(self::quantity(quantity)),
+                ExgineCharge::Percent => Charge::Percent(self::quantity(quantity)),
+            };
+            rate.0.fee = Some(Fee { asset, charge });
+            ExgineStatus::Ok
+        }
+        _ => ExgineStatus::InvalidArgument,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn exgine_market_new() -> *mut ExgineMarket {
+    Box::into_raw(Box::new(ExgineMarket(hashmap![])))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_market_free(market: *mut ExgineMarket) {
+    free(market)
+}
+
+/// Registers a copy of `rate` under `name`, replacing any previous rate.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_market_register_rate(
+    market: *mut ExgineMarket,
+    name: *const c_char,
+    rate: *const ExgineRate,
+) -> ExgineStatus {
+    match (market.as_mut(), string(name), rate.as_ref()) {
+        (Some(market), Some(name), Some(rate)) => {
+            market.0.insert(name, rate.0.clone());
+            ExgineStatus::Ok
+        }
+        _ => ExgineStatus::InvalidArgument,
+    }
+}
+
+/// Runs `Account::exchange_with_fee` with the named rate. When approved,
+/// `buyer`, `seller` and `fee_account` are updated in place. When denied, they
+/// are left untouched and, if `denial` is not null, `*denial` receives a
+/// handle to the deficits. `fee_account` may be null only if the rate charges
+/// no fee, and must differ from both sides. Panics are caught and reported as
+/// `Panicked` rather than unwinding into C.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_exchange(
+    market: *const ExgineMarket,
+    rate: *const c_char,
+    quantity: ExgineQuantity,
+    buyer: *mut ExgineAccount,
+    seller: *mut ExgineAccount,
+    fee_account: *mut ExgineAccount,
+    denial: *mut *mut ExgineDenial,
+) -> ExgineStatus {
+    if !denial.is_null() {
+        *denial = ptr::null_mut();
+    }
+    if ptr::eq(buyer, seller) || ptr::eq(fee_account, buyer) || ptr::eq(fee_account, seller) {
+        return ExgineStatus::InvalidArgument;
+    }
+    let (market, rate, buyer, seller) =
+        match (market.as_ref(), string(rate), buyer.as_mut(), seller.as_mut()) {
+            (Some(market), Some(rate), Some(buyer), Some(seller)) => (market, rate, buyer, seller),
+            _ => return ExgineStatus::InvalidArgument,
+        };
+    let rate = match market.0.get(&rate) {
+        Some(rate) => rate,
+        None => return ExgineStatus::UnknownRate,
+    };
+    let mut fee_account = fee_account.as_mut();
+    if rate.fee.is_some() && fee_account.is_none() {
+        return ExgineStatus::InvalidArgument;
+    }
+    let untouched = Account::default();
+    let charged = match &fee_account {
+        Some(fee_account) => &fee_account.0,
+        None => &untouched,
+    };
+    let exchange = || {
+        let quantity = self::quantity(quantity);
+        Account::exchange_with_fee(rate, quantity, &buyer.0, &seller.0, charged)
+    };
+    let tranx = match panic::catch_unwind(AssertUnwindSafe(exchange)) {
+        Ok(tranx) => tranx,
+        Err(_) => return ExgineStatus::Panicked,
+    };
+    match tranx {
+        Tranx::Approved(buyer_account, seller_account, charged) => {
+            buyer.0 = buyer_account;
+            seller.0 = seller_account;
+            if let Some(fee_account) = fee_account.as_mut() {
+                fee_account.0 = charged;
+            }
+            ExgineStatus::Ok
+        }
+        Tranx::Denied(deficit) => {
+            if !denial.is_null() {
+                let mut deficit: Vec<(CString, Quantity)> = deficit
+                    .into_iter()
+                    .filter_map(|(asset, quantity)| Some((CString::new(asset).ok()?, quantity)))
+                    .collect();
+                deficit.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+                *denial = Box::into_raw(Box::new(ExgineDenial(deficit)));
+            }
+            ExgineStatus::Denied
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_denial_free(denial: *mut ExgineDenial) {
+    free(denial)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn exgine_denial_len(denial: *const ExgineDenial) -> usize {
+    denial.as_ref().map_or(0, |denial| denial.0.len())
+}
+
+/// The asset of the `index`th deficit, sorted by asset name. The string is
+/// owned by the denial and lives until it is freed.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_denial_asset(
+    denial: *const ExgineDenial,
+    index: usize,
+) -> *const c_char {
+    denial
+        .as_ref()
+        .and_then(|denial| denial.0.get(index))
+        .map_or(ptr::null(), |(asset, _)| asset.as_ptr())
+}
+
+/// How far beyond its credit limit, after holds, the side paying the `index`th
+/// asset would end up: the buyer for the debit and fee, the seller for the
+/// credit. A negative fee is reported as the fee itself.
+/// Accounts made through this API have no credit lines or holds, so this is
+/// the negative balance that side would be left with.
+#[no_mangle]
+pub unsafe extern "C" fn exgine_denial_quantity(
+    denial: *const ExgineDenial,
+    index: usize,
+) -> ExgineQuantity {
+    denial
+        .as_ref()
+        .and_then(|denial| denial.0.get(index))
+        .map_or(0, |(_, quantity)| bits(*quantity))
+}
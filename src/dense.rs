@@ -0,0 +1,354 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::account::{fixed_amount, Account, Quantity, I32F32};
+use crate::asset::Asset;
+use crate::map::Map;
+use crate::rate::{Charge, Fee, Rate};
+
+/// Maps assets to dense indices `0..len()`, in order of first use, so
+/// accounts can store balances in a vector instead of a map.
+#[derive(Debug, Clone)]
+pub struct Interner<TAsset: Asset> {
+    assets: Vec<TAsset>,
+    indices: Map<TAsset, usize>,
+}
+
+/// Balances, credit limits and holds indexed by interned asset. Indices past
+/// the end hold zero.
+#[derive(Debug, Clone, Default)]
+pub struct DenseAccount {
+    balances: Vec<I32F32>,
+    credit: Vec<I32F32>,
+    held: Vec<I32F32>,
+}
+
+/// A rate over interned assets, with the same semantics as `Rate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenseRate {
+    pub credit: Vec<(usize, Quantity)>,
+    pub debit: Vec<(usize, Quantity)>,
+    pub fee: Option<(usize, Charge)>,
+}
+
+pub enum DenseTranx {
+    Approved(DenseAccount, DenseAccount, DenseAccount),
+    /// Deficits by asset index, in increasing index order.
+    Denied(Vec<(usize, Quantity)>),
+}
+
+impl<TAsset: Asset> Default for Interner<TAsset> {
+    fn default() -> Self {
+        Interner {
+            assets: Vec::new(),
+            indices: hashmap![],
+        }
+    }
+}
+
+impl<TAsset: Asset> Interner<TAsset> {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, asset: &TAsset) -> usize {
+        if let Some(index) = self.indices.get(asset) {
+            return *index;
+        }
+        let index = self.assets.len();
+        self.assets.push(asset.clone());
+        self.indices.insert(asset.clone(), index);
+        index
+    }
+
+    pub fn index(&self, asset: &TAsset) -> Option<usize> {
+        self.indices.get(asset).copied()
+    }
+
+    pub fn asset(&self, index: usize) -> Option<&TAsset> {
+        self.assets.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Interns the account's balances, credit lines and holds.
+    pub fn account(&mut self, account: &Account<TAsset>) -> DenseAccount {
+        let mut side = |side: &Map<TAsset, Quantity>| {
+            let mut dense = Vec::new();
+            for (asset, Quantity::Amount(amount)) in side {
+                add(&mut dense, self.intern(asset), *amount);
+            }
+            dense
+        };
+        DenseAccount {
+            balances: side(&account.balances),
+            credit: side(&account.credit),
+            held: side(&account.held),
+        }
+    }
+
+    pub fn rate(&mut self, rate: &Rate<TAsset>) -> DenseRate {
+        let mut side = |side: &Map<TAsset, Quantity>| {
+            let mut side: Vec<(usize, Quantity)> = side
+                .iter()
+                .map(|(asset, quantity)| (self.intern(asset), *quantity))
+                .collect();
+            side.sort_by_key(|(index, _)| *index);
+            side
+        };
+        let (credit, debit) = (side(&rate.credit), side(&rate.debit));
+        DenseRate {
+            credit,
+            debit,
+            fee: rate
+                .fee
+                .as_ref()
+                .map(|Fee { asset, charge }| (self.intern(asset), *charge)),
+        }
+    }
+
+    /// Converts back to a map-backed account, skipping zero balances, limits
+    /// and holds.
+    pub fn sparse(&self, account: &DenseAccount) -> Account<TAsset> {
+        let side = |side: &[I32F32]| -> Map<TAsset, Quantity> {
+            side.iter()
+                .enumerate()
+                .filter(|(_, amount)| **amount != 0)
+                .map(|(index, amount)| (self.assets[index].clone(), Quantity::Amount(*amount)))
+                .collect()
+        };
+        Account {
+            balances: side(&account.balances),
+            credit: side(&account.credit),
+            held: side(&account.held),
+        }
+    }
+
+    pub fn deficit(&self, deficit: &[(usize, Quantity)]) -> Map<TAsset, Quantity> {
+        deficit
+            .iter()
+            .map(|(index, quantity)| (self.assets[*index].clone(), *quantity))
+            .collect()
+    }
+}
+
+fn at(side: &[I32F32], index: usize) -> I32F32 {
+    side.get(index).copied().unwrap_or_else(|| I32F32::from(0))
+}
+
+fn add(side: &mut Vec<I32F32>, index: usize, delta: I32F32) {
+    if index >= side.len() {
+        side.resize(index + 1, I32F32::from(0));
+    }
+    side[index] += delta;
+}
+
+// Per-index sum of the balances of `accounts`.
+#[cfg(debug_assertions)]
+fn total(accounts: &[&DenseAccount]) -> Vec<I32F32> {
+    let mut total = Vec::new();
+    for account in accounts {
+        for (index, amount) in account.balances.iter().enumerate() {
+            add(&mut total, index, *amount);
+        }
+    }
+    total
+}
+
+// Same as `invariant::verify`, index by index.
+#[cfg(debug_assertions)]
+fn verify(operation: &str, before: &[I32F32], after: &[I32F32]) {
+    let delta: Vec<(usize, Quantity)> = (0..before.len().max(after.len()))
+        .filter(|index| at(after, *index) != at(before, *index))
+        .map(|index| {
+            (
+                index,
+                Quantity::Amount(at(after, index) - at(before, index)),
+            )
+        })
+        .collect();
+    if !delta.is_empty() {
+        panic!("{} created or destroyed assets: {:?}", operation, delta);
+    }
+}
+
+impl DenseAccount {
+    /// Total balance, held amounts included.
+    pub fn quantity(&self, index: usize) -> Quantity {
+        Quantity::Amount(self.amount(index))
+    }
+
+    pub fn credit(&self, index: usize) -> Quantity {
+        Quantity::Amount(at(&self.credit, index))
+    }
+
+    pub fn held(&self, index: usize) -> Quantity {
+        Quantity::Amount(at(&self.held, index))
+    }
+
+    fn amount(&self, index: usize) -> I32F32 {
+        at(&self.balances, index)
+    }
+
+    fn adjust(&mut self, index: usize, delta: I32F32) {
+        add(&mut self.balances, index, delta);
+    }
+
+    /// Same as `Account::beyond_credit`.
+    fn beyond_credit(&self, index: usize, balance: I32F32) -> Option<I32F32> {
+        let headroom = balance - at(&self.held, index) + at(&self.credit, index);
+        if headroom < 0 {
+            Some(headroom)
+        } else {
+            None
+        }
+    }
+
+    fn fee(rate: &DenseRate, q: I32F32) -> Option<(usize, I32F32)> {
+        rate.fee.map(|(index, charge)| {
+            let debited = rate
+                .debit
+                .iter()
+                .find(|(asset, _)| *asset == index)
+                .map_or(fixed_amount(0), |(_, Quantity::Amount(amount))| {
+                    Quantity::Amount(*amount * q)
+                });
+            let Quantity::Amount(amount) = charge.amount(debited);
+            (index, amount)
+        })
+    }
+
+    /// The buyer's deficits on what it pays and the seller's on what it
+    /// delivers, or a negative fee, if any, without touching the accounts.
+    fn deficit(
+        rate: &DenseRate,
+        q: I32F32,
+        buyer: &DenseAccount,
+        seller: &DenseAccount,
+    ) -> Vec<(usize, Quantity)> {
+        let fee = DenseAccount::fee(rate, q);
+        let scaled = |side: &[(usize, Quantity)], index: usize| {
+            side.iter()
+                .filter(|(asset, _)| *asset == index)
+                .fold(I32F32::from(0), |total, (_, Quantity::Amount(amount))| {
+                    total + *amount * q
+                })
+        };
+        let has = |side: &[(usize, Quantity)], index: usize| {
+            side.iter().any(|(asset, _)| *asset == index)
+        };
+        let mut touched: Vec<usize> = rate
+            .credit
+            .iter()
+            .chain(&rate.debit)
+            .map(|(index, _)| *index)
+            .collect();
+        touched.extend(fee.map(|(index, _)| index));
+        touched.sort_unstable();
+        touched.dedup();
+        touched
+            .into_iter()
+            .filter_map(|index| {
+                let fee = match fee {
+                    Some((asset, amount)) if asset == index => Some(amount),
+                    _ => None,
+                };
+                let (credit, debit) = (scaled(&rate.credit, index), scaled(&rate.debit, index));
+                let buyer_after =
+                    buyer.amount(index) - debit - fee.unwrap_or_else(|| I32F32::from(0)) + credit;
+                let seller_after = seller.amount(index) - credit + debit;
+                let paid = has(&rate.debit, index) || fee.is_some();
+                let delivered = has(&rate.credit, index);
+                fee.filter(|amount| *amount < 0)
+                    .or_else(|| {
+                        paid.then(|| buyer.beyond_credit(index, buyer_after))
+                            .flatten()
+                    })
+                    .or_else(|| {
+                        delivered
+                            .then(|| seller.beyond_credit(index, seller_after))
+                            .flatten()
+                    })
+                    .map(|beyond| (index, Quantity::Amount(beyond)))
+            })
+            .collect()
+    }
+
+    pub fn exchange(
+        rate: &DenseRate,
+        quantity: Quantity,
+        buyer: &DenseAccount,
+        seller: &DenseAccount,
+    ) -> DenseTranx {
+        DenseAccount::exchange_with_fee(rate, quantity, buyer, seller, &DenseAccount::default())
+    }
+
+    pub fn exchange_with_fee(
+        rate: &DenseRate,
+        quantity: Quantity,
+        buyer: &DenseAccount,
+        seller: &DenseAccount,
+        fee_account: &DenseAccount,
+    ) -> DenseTranx {
+        let (mut buyer, mut seller, mut fee_account) =
+            (buyer.clone(), seller.clone(), fee_account.clone());
+        match DenseAccount::exchange_in_place(
+            rate,
+            quantity,
+            &mut buyer,
+            &mut seller,
+            &mut fee_account,
+        ) {
+            Ok(()) => DenseTranx::Approved(buyer, seller, fee_account),
+            Err(deficit) => DenseTranx::Denied(deficit),
+        }
+    }
+
+    pub fn exchange_in_place(
+        rate: &DenseRate,
+        quantity: Quantity,
+        buyer: &mut DenseAccount,
+        seller: &mut DenseAccount,
+        fee_account: &mut DenseAccount,
+    ) -> Result<(), Vec<(usize, Quantity)>> {
+        let Quantity::Amount(q) = quantity;
+        let deficit = DenseAccount::deficit(rate, q, buyer, seller);
+        if !deficit.is_empty() {
+            return Err(deficit);
+        }
+        #[cfg(debug_assertions)]
+        let before = total(&[buyer, seller, fee_account]);
+        for (index, Quantity::Amount(amount)) in &rate.debit {
+            buyer.adjust(*index, -(*amount * q));
+            seller.adjust(*index, *amount * q);
+        }
+        if let Some((index, amount)) = DenseAccount::fee(rate, q) {
+            buyer.adjust(index, -amount);
+            fee_account.adjust(index, amount);
+        }
+        for (index, Quantity::Amount(amount)) in &rate.credit {
+            buyer.adjust(*index, *amount * q);
+            seller.adjust(*index, -(*amount * q));
+        }
+        #[cfg(debug_assertions)]
+        verify(
+            "DenseAccount::exchange",
+            &before,
+            &total(&[buyer, seller, fee_account]),
+        );
+        Ok(())
+    }
+}
+
+impl PartialEq for DenseAccount {
+    fn eq(&self, rhs: &DenseAccount) -> bool {
+        let len = self.balances.len().max(rhs.balances.len());
+        (0..len).all(|index| self.amount(index) == rhs.amount(index))
+    }
+}
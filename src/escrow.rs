@@ -0,0 +1,119 @@
+use crate::account::{Account, Quantity};
+use crate::asset::Asset;
+#[cfg(debug_assertions)]
+use crate::invariant;
+use crate::map::Map;
+use crate::rate::Rate;
+
+/// Assets taken from both sides of an exchange in phase one, awaiting commit
+/// or refund. `expires` is the first simulation tick at which the escrow can
+/// no longer commit.
+#[derive(Debug, Clone)]
+pub struct Escrow<TAsset: Asset> {
+    /// Paid in by the buyer, without the fee.
+    pub debit: Account<TAsset>,
+    /// Paid in by the seller.
+    pub credit: Account<TAsset>,
+    /// Paid in by the buyer, owed to the fee account on commit.
+    pub fees: Account<TAsset>,
+    pub expires: u64,
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum Preparation<TAsset: Asset> {
+    Prepared(Escrow<TAsset>, Account<TAsset>, Account<TAsset>),
+    Denied(Map<TAsset, Quantity>),
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum Settlement<TAsset: Asset> {
+    Committed(Account<TAsset>, Account<TAsset>, Account<TAsset>),
+    Refunded(Account<TAsset>, Account<TAsset>),
+}
+
+impl<TAsset: Asset> Escrow<TAsset> {
+    /// Phase one: moves the buyer's debit and fee, and the seller's credit,
+    /// into escrow until tick `expires`. Denied with the same deficit as
+    /// `Account::exchange_with_fee` when the buyer cannot pay or the seller
+    /// cannot deliver.
+    pub fn prepare(
+        rate: &Rate<TAsset>,
+        quantity: Quantity,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        expires: u64,
+    ) -> Preparation<TAsset> {
+        let quote = Account::quote(rate, quantity, buyer, seller);
+        if !quote.approved() {
+            return Preparation::Denied(quote.deficit);
+        }
+        let escrow = Escrow {
+            debit: Account::from(quote.debit),
+            credit: Account::from(quote.credit),
+            fees: Account::from(quote.fees),
+            expires,
+        };
+        #[cfg(debug_assertions)]
+        let before = [buyer, seller];
+        let buyer = &(buyer - &escrow.debit) - &escrow.fees;
+        let seller = seller - &escrow.credit;
+        #[cfg(debug_assertions)]
+        invariant::verify(
+            "Escrow::prepare",
+            before,
+            [&buyer, &seller, &escrow.account()],
+        );
+        Preparation::Prepared(escrow, buyer, seller)
+    }
+
+    /// Everything held in escrow.
+    pub fn account(&self) -> Account<TAsset> {
+        &(&self.debit + &self.credit) + &self.fees
+    }
+
+    pub fn expired(&self, tick: u64) -> bool {
+        tick >= self.expires
+    }
+
+    /// Phase two: at `tick`, delivers the credit to the buyer, the debit to
+    /// the seller and the fees to `fee_account`, with the same balances
+    /// `Account::exchange_with_fee` would have produced. Refunds both sides
+    /// instead once the escrow has expired.
+    pub fn commit(
+        self,
+        tick: u64,
+        buyer: &Account<TAsset>,
+        seller: &Account<TAsset>,
+        fee_account: &Account<TAsset>,
+    ) -> Settlement<TAsset> {
+        if self.expired(tick) {
+            return self.refund(buyer, seller);
+        }
+        let (committed_buyer, committed_seller, committed_fees) = (
+            buyer + &self.credit,
+            seller + &self.debit,
+            fee_account + &self.fees,
+        );
+        #[cfg(debug_assertions)]
+        invariant::verify(
+            "Escrow::commit",
+            [buyer, seller, fee_account, &self.account()],
+            [&committed_buyer, &committed_seller, &committed_fees],
+        );
+        Settlement::Committed(committed_buyer, committed_seller, committed_fees)
+    }
+
+    /// Phase two, aborted: returns the debit and fees to the buyer and the
+    /// credit to the seller.
+    pub fn refund(self, buyer: &Account<TAsset>, seller: &Account<TAsset>) -> Settlement<TAsset> {
+        let (refunded_buyer, refunded_seller) =
+            (&(buyer + &self.debit) + &self.fees, seller + &self.credit);
+        #[cfg(debug_assertions)]
+        invariant::verify(
+            "Escrow::refund",
+            [buyer, seller, &self.account()],
+            [&refunded_buyer, &refunded_seller],
+        );
+        Settlement::Refunded(refunded_buyer, refunded_seller)
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(all(debug_assertions, not(feature = "std")))]
+use alloc::vec::Vec;
+
+use crate::account::{fixed_amount, Account, Quantity};
+use crate::asset::Asset;
+use crate::map::Map;
+
+/// Sum of every account in a registry, asset by asset.
+pub fn total<'a, TAsset, I>(accounts: I) -> Account<TAsset>
+where
+    TAsset: Asset + 'a,
+    I: IntoIterator<Item = &'a Account<TAsset>>,
+{
+    accounts
+        .into_iter()
+        .fold(Account::default(), |total, account| &total + account)
+}
+
+/// Checks that the accounts `after` hold exactly the assets held `before`.
+/// Otherwise returns every asset that was created or destroyed, with its delta
+/// (after minus before).
+pub fn conserved<'a, 'b, TAsset, B, A>(before: B, after: A) -> Result<(), Map<TAsset, Quantity>>
+where
+    TAsset: Asset + 'a + 'b,
+    B: IntoIterator<Item = &'a Account<TAsset>>,
+    A: IntoIterator<Item = &'b Account<TAsset>>,
+{
+    let delta = &total(after) - &total(before);
+    let delta: Map<TAsset, Quantity> = delta
+        .assets()
+        .iter()
+        .filter(|(_, quantity)| **quantity != fixed_amount(0))
+        .map(|(asset, quantity)| (asset.clone(), *quantity))
+        .collect();
+    if delta.is_empty() {
+        Ok(())
+    } else {
+        Err(delta)
+    }
+}
+
+/// Panics with the delta if `operation` turned the accounts `before` into
+/// accounts `after` without conserving them. Debug builds run it after every
+/// exchange. Assets need not be `Debug`, so only the quantities are shown;
+/// `conserved` names the assets.
+#[cfg(debug_assertions)]
+pub(crate) fn verify<'a, 'b, TAsset, B, A>(operation: &str, before: B, after: A)
+where
+    TAsset: Asset + 'a + 'b,
+    B: IntoIterator<Item = &'a Account<TAsset>>,
+    A: IntoIterator<Item = &'b Account<TAsset>>,
+{
+    if let Err(delta) = conserved(before, after) {
+        let delta: Vec<&Quantity> = delta.values().collect();
+        panic!("{} created or destroyed assets: {:?}", operation, delta);
+    }
+}
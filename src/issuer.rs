@@ -0,0 +1,88 @@
+use crate::account::{fixed_amount, Account, Quantity};
+use crate::asset::Asset;
+use crate::map::Map;
+use core::iter;
+
+/// The only source of new units of `asset`. Tracks the total supply in
+/// circulation and refuses to mint past `cap`, if there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issuer<TAsset: Asset> {
+    pub asset: TAsset,
+    pub cap: Option<Quantity>,
+    supply: Quantity,
+}
+
+pub enum Issuance<TAsset: Asset> {
+    Approved(Issuer<TAsset>, Account<TAsset>),
+    Denied(Map<TAsset, Quantity>),
+}
+
+impl<TAsset: Asset> Issuer<TAsset> {
+    pub fn new(asset: TAsset, cap: Option<Quantity>) -> Self {
+        Issuer {
+            asset,
+            cap,
+            supply: fixed_amount(0),
+        }
+    }
+
+    pub fn supply(&self) -> Quantity {
+        self.supply
+    }
+
+    /// Issues `quantity` new units into `account`. Denied with the (negative)
+    /// headroom left under the cap if it would be exceeded, or with `quantity`
+    /// itself if it is not positive or the supply or balance would overflow.
+    pub fn mint(&self, quantity: Quantity, account: &Account<TAsset>) -> Issuance<TAsset> {
+        let (Quantity::Amount(supply), Quantity::Amount(amount)) = (self.supply, quantity);
+        let Quantity::Amount(balance) = account.quantity(&self.asset);
+        let supply = match supply.checked_add(amount) {
+            Some(supply) if amount > 0 && balance.checked_add(amount).is_some() => supply,
+            _ => return Issuance::Denied(hashmap![self.asset.clone() => quantity]),
+        };
+        if let Some(Quantity::Amount(cap)) = self.cap {
+            if supply > cap {
+                return Issuance::Denied(hashmap![
+                    self.asset.clone() => Quantity::Amount(cap - supply)
+                ]);
+            }
+        }
+        self.issue(Quantity::Amount(supply), account + &self.units(quantity))
+    }
+
+    /// Destroys `quantity` units of `account`. Like an exchange, it may only
+    /// spend what is not held, down to the account's credit limit. Denied with
+    /// how far beyond the limit the balance would fall, with the (negative)
+    /// shortfall if the supply is smaller than `quantity`, or with `quantity`
+    /// itself if it is not positive.
+    pub fn burn(&self, quantity: Quantity, account: &Account<TAsset>) -> Issuance<TAsset> {
+        let (Quantity::Amount(supply), Quantity::Amount(amount)) = (self.supply, quantity);
+        if amount <= 0 {
+            return Issuance::Denied(hashmap![self.asset.clone() => quantity]);
+        }
+        if amount > supply {
+            return Issuance::Denied(hashmap![
+                self.asset.clone() => Quantity::Amount(supply - amount)
+            ]);
+        }
+        let account = account - &self.units(quantity);
+        let deficit = account.shortfall(iter::once(&self.asset));
+        if deficit.is_empty() {
+            self.issue(Quantity::Amount(supply - amount), account)
+        } else {
+            Issuance::Denied(deficit)
+        }
+    }
+
+    fn units(&self, quantity: Quantity) -> Account<TAsset> {
+        Account::from(hashmap![self.asset.clone() => quantity])
+    }
+
+    fn issue(&self, supply: Quantity, account: Account<TAsset>) -> Issuance<TAsset> {
+        let issuer = Issuer {
+            supply,
+            ..self.clone()
+        };
+        Issuance::Approved(issuer, account)
+    }
+}
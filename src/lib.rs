@@ -1,5 +1,27 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 #[macro_use]
 pub mod macros;
+pub mod map;
 pub mod rate;
 pub mod asset;
 pub mod account;
+pub mod invariant;
+pub mod issuer;
+pub mod stats;
+pub mod dense;
+pub mod batch;
+pub mod escrow;
+pub mod multi;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
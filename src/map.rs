@@ -0,0 +1,20 @@
+#[cfg(all(feature = "std", not(feature = "ordered")))]
+pub use std::collections::HashMap as Map;
+
+#[cfg(all(feature = "std", feature = "ordered"))]
+pub use std::collections::BTreeMap as Map;
+
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::BTreeMap as Map;
+
+#[doc(hidden)]
+#[cfg(all(feature = "std", not(feature = "ordered")))]
+pub fn with_capacity<K: Eq + core::hash::Hash, V>(capacity: usize) -> Map<K, V> {
+    Map::with_capacity(capacity)
+}
+
+#[doc(hidden)]
+#[cfg(any(not(feature = "std"), feature = "ordered"))]
+pub fn with_capacity<K: Ord, V>(_capacity: usize) -> Map<K, V> {
+    Map::new()
+}
@@ -0,0 +1,116 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::account::{Account, Quantity};
+use crate::asset::Asset;
+#[cfg(debug_assertions)]
+use crate::invariant;
+use crate::map::Map;
+
+/// Moves `amount` of `asset`, per unit exchanged, from the account bound to
+/// role `from` to the account bound to role `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg<TRole: Asset, TAsset: Asset> {
+    pub from: TRole,
+    pub to: TRole,
+    pub asset: TAsset,
+    pub amount: Quantity,
+}
+
+/// A rate between any number of named roles, such as buyer, supplier and
+/// royalty holder. Roles are keyed like assets, so any `Asset` type, `String`
+/// included, can name them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiRate<TRole: Asset, TAsset: Asset> {
+    pub legs: Vec<Leg<TRole, TAsset>>,
+}
+
+pub enum MultiTranx<TRole: Asset, TAsset: Asset> {
+    /// Every bound account after the exchange, by role.
+    Approved(Map<TRole, Account<TAsset>>),
+    /// Deficits by role, for every participant that cannot pay its legs.
+    Denied(Map<TRole, Map<TAsset, Quantity>>),
+    /// Roles of the rate without an account bound to them.
+    Unbound(Vec<TRole>),
+}
+
+impl<TRole: Asset, TAsset: Asset> Default for MultiRate<TRole, TAsset> {
+    fn default() -> Self {
+        MultiRate { legs: Vec::new() }
+    }
+}
+
+impl<TRole: Asset, TAsset: Asset> MultiRate<TRole, TAsset> {
+    /// Every role with a leg in this rate, in order of first appearance.
+    pub fn roles(&self) -> Vec<&TRole> {
+        let mut roles: Vec<&TRole> = Vec::new();
+        for leg in &self.legs {
+            for role in [&leg.from, &leg.to] {
+                if !roles.contains(&role) {
+                    roles.push(role);
+                }
+            }
+        }
+        roles
+    }
+
+    /// Runs every leg, scaled by `quantity`, between the accounts bound to
+    /// each role. Every participant must afford what it pays, within its
+    /// credit lines and after its holds, or the whole exchange is denied.
+    /// Accounts bound to roles outside the rate come back unchanged.
+    pub fn exchange(
+        &self,
+        quantity: Quantity,
+        parties: &Map<TRole, Account<TAsset>>,
+    ) -> MultiTranx<TRole, TAsset> {
+        let unbound: Vec<TRole> = self
+            .roles()
+            .into_iter()
+            .filter(|role| !parties.contains_key(role))
+            .cloned()
+            .collect();
+        if !unbound.is_empty() {
+            return MultiTranx::Unbound(unbound);
+        }
+        let Quantity::Amount(q) = quantity;
+        let mut outflows: Map<TRole, Account<TAsset>> = hashmap![];
+        let mut inflows: Map<TRole, Account<TAsset>> = hashmap![];
+        for Leg {
+            from,
+            to,
+            asset,
+            amount: Quantity::Amount(amount),
+        } in &self.legs
+        {
+            let units = Account::from(hashmap![asset.clone() => Quantity::Amount(*amount * q)]);
+            *outflows.entry(from.clone()).or_default() += &units;
+            *inflows.entry(to.clone()).or_default() += &units;
+        }
+        let mut settled: Map<TRole, Account<TAsset>> = hashmap![];
+        let mut deficit: Map<TRole, Map<TAsset, Quantity>> = hashmap![];
+        for (role, account) in parties {
+            let mut after = account.clone();
+            if let Some(paid) = outflows.get(role) {
+                after -= paid;
+            }
+            if let Some(received) = inflows.get(role) {
+                after += received;
+            }
+            let short = outflows
+                .get(role)
+                .map(|paid| Account::shortfall(&after, paid.assets().keys()))
+                .unwrap_or_default();
+            if !short.is_empty() {
+                deficit.insert(role.clone(), short);
+            }
+            settled.insert(role.clone(), after);
+        }
+        if deficit.is_empty() {
+            #[cfg(debug_assertions)]
+            invariant::verify("MultiRate::exchange", parties.values(), settled.values());
+            MultiTranx::Approved(settled)
+        } else {
+            MultiTranx::Denied(deficit)
+        }
+    }
+}
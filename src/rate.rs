@@ -1,9 +1,77 @@
 use crate::account::*;
 use crate::asset::*;
-use std::collections::HashMap;
+use crate::map::Map;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charge {
+    /// A fixed amount per exchange, whatever the quantity.
+    Flat(Quantity),
+    /// A percentage of what the buyer is debited in the fee asset. Nothing is
+    /// debited in an asset the rate does not debit, so the fee is then zero.
+    Percent(Quantity),
+}
+
+/// Paid by the buyer, in `asset`, to the fee account of an exchange. An
+/// exchange whose fee comes out negative is denied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fee<TAsset: Asset> {
+    pub asset: TAsset,
+    pub charge: Charge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rate<TAsset: Asset> {
-    pub credit: HashMap<TAsset, Quantity>,
-    pub debit: HashMap<TAsset, Quantity>,
+    pub credit: Map<TAsset, Quantity>,
+    pub debit: Map<TAsset, Quantity>,
+    pub fee: Option<Fee<TAsset>>,
+}
+
+/// Consumes `inputs` and produces `outputs` within a single account, with no
+/// counterparty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe<TAsset: Asset> {
+    pub inputs: Map<TAsset, Quantity>,
+    pub outputs: Map<TAsset, Quantity>,
+}
+
+impl Charge {
+    /// The fee on an exchange that debits the buyer `debited` of the fee asset.
+    /// Percentages are taken at double width, so large debits cannot overflow;
+    /// a fee beyond the range of `Quantity` saturates.
+    pub fn amount(&self, debited: Quantity) -> Quantity {
+        match (*self, debited) {
+            (Charge::Flat(amount), _) => amount,
+            (Charge::Percent(Quantity::Amount(percent)), Quantity::Amount(debited)) => {
+                let bits = i128::from(debited.to_bits()) * i128::from(percent.to_bits())
+                    / i128::from(I32F32::from(100).to_bits());
+                let bits = bits.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64;
+                Quantity::Amount(I32F32::from_bits(bits))
+            }
+        }
+    }
+}
+
+impl<TAsset: Asset> Fee<TAsset> {
+    pub fn amount(&self, debited: Quantity) -> Quantity {
+        self.charge.amount(debited)
+    }
+}
+
+impl<TAsset: Asset> Default for Rate<TAsset> {
+    fn default() -> Self {
+        Rate {
+            credit: hashmap![],
+            debit: hashmap![],
+            fee: None,
+        }
+    }
+}
+
+impl<TAsset: Asset> Default for Recipe<TAsset> {
+    fn default() -> Self {
+        Recipe {
+            inputs: hashmap![],
+            outputs: hashmap![],
+        }
+    }
 }
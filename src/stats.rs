@@ -0,0 +1,95 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::account::{fixed_amount, Account, Quantity, I32F32};
+use crate::asset::Asset;
+use core::cmp::Reverse;
+use core::convert::TryFrom;
+
+/// How one asset is spread across a set of accounts, keyed by whatever names
+/// the caller uses for them. Accounts without the asset count as holding zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution<K> {
+    /// Every account's balance, largest first.
+    pub balances: Vec<(K, Quantity)>,
+}
+
+fn amount(Quantity::Amount(amount): Quantity) -> I32F32 {
+    amount
+}
+
+impl<K> Distribution<K> {
+    pub fn of<'a, TAsset, I>(asset: &TAsset, accounts: I) -> Self
+    where
+        TAsset: Asset + 'a,
+        I: IntoIterator<Item = (K, &'a Account<TAsset>)>,
+    {
+        let mut balances: Vec<(K, Quantity)> = accounts
+            .into_iter()
+            .map(|(key, account)| (key, account.quantity(asset)))
+            .collect();
+        balances.sort_by_key(|(_, q)| Reverse(amount(*q)));
+        Distribution { balances }
+    }
+
+    /// The sum of every balance, or `None` if it does not fit a `Quantity`.
+    /// Balances are summed at double width, so only the result can overflow.
+    pub fn total(&self) -> Option<Quantity> {
+        let bits: i128 = self
+            .balances
+            .iter()
+            .map(|(_, q)| i128::from(amount(*q).to_bits()))
+            .sum();
+        i64::try_from(bits)
+            .ok()
+            .map(|bits| Quantity::Amount(I32F32::from_bits(bits)))
+    }
+
+    /// Accounts holding a positive balance.
+    pub fn holder_count(&self) -> usize {
+        self.balances.iter().filter(|(_, q)| amount(*q) > 0).count()
+    }
+
+    pub fn top(&self, n: usize) -> &[(K, Quantity)] {
+        &self.balances[..n.min(self.balances.len())]
+    }
+
+    /// The middle balance, or the mean of the two middle ones for an even
+    /// number of accounts.
+    pub fn median(&self) -> Quantity {
+        let len = self.balances.len();
+        if len == 0 {
+            return fixed_amount(0);
+        }
+        let middle = amount(self.balances[len / 2].1);
+        if len % 2 == 1 {
+            Quantity::Amount(middle)
+        } else {
+            let below = amount(self.balances[len / 2 - 1].1);
+            Quantity::Amount(below + (middle - below) / I32F32::from(2))
+        }
+    }
+
+    /// 0 when every account holds the same balance, approaching 1 as a single
+    /// account holds everything. Computed in `f64`, so it holds for totals
+    /// beyond the range of `Quantity`.
+    pub fn gini(&self) -> f64 {
+        let n = self.balances.len() as f64;
+        let total: f64 = self
+            .balances
+            .iter()
+            .map(|(_, q)| amount(*q).to_num::<f64>())
+            .sum();
+        if self.balances.is_empty() || total == 0.0 {
+            return 0.0;
+        }
+        let weighted: f64 = self
+            .balances
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(rank, (_, q))| (rank + 1) as f64 * amount(*q).to_num::<f64>())
+            .sum();
+        2.0 * weighted / (n * total) - (n + 1.0) / n
+    }
+}
@@ -0,0 +1,194 @@
+use crate::account::{self, Quantity, Tranx, I32F32};
+use crate::map::Map;
+use crate::rate::{self, Charge, Fee};
+use wasm_bindgen::prelude::*;
+
+type Asset = String;
+
+fn parse(quantity: &str) -> Result<Quantity, JsValue> {
+    quantity
+        .trim()
+        .parse::<I32F32>()
+        .map(Quantity::Amount)
+        .map_err(|_| JsValue::from_str(&format!("invalid quantity `{}`", quantity)))
+}
+
+fn format(Quantity::Amount(amount): Quantity) -> String {
+    amount.to_string()
+}
+
+fn keys(assets: &Map<Asset, Quantity>) -> Box<[JsValue]> {
+    let mut keys: Vec<&Asset> = assets.keys().collect();
+    keys.sort();
+    keys.into_iter().map(|asset| JsValue::from_str(asset)).collect()
+}
+
+fn set(assets: &mut Map<Asset, Quantity>, asset: &str, quantity: &str) -> Result<(), JsValue> {
+    assets.insert(asset.to_string(), parse(quantity)?);
+    Ok(())
+}
+
+#[wasm_bindgen(js_name = Account)]
+#[derive(Clone, Default)]
+pub struct WasmAccount(account::Account<Asset>);
+
+#[wasm_bindgen(js_class = Account)]
+impl WasmAccount {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmAccount {
+        WasmAccount::default()
+    }
+
+    pub fn quantity(&self, asset: &str) -> String {
+        format(self.0.quantity(&asset.to_string()))
+    }
+
+    /// Sets one balance, leaving the rest of the account as it is.
+    #[wasm_bindgen(js_name = setQuantity)]
+    pub fn set_quantity(&mut self, asset: &str, quantity: &str) -> Result<(), JsValue> {
+        set(&mut self.0.balances, asset, quantity)
+    }
+
+    pub fn assets(&self) -> Box<[JsValue]> {
+        keys(self.0.assets())
+    }
+}
+
+#[wasm_bindgen(js_name = Rate)]
+#[derive(Default)]
+pub struct WasmRate(rate::Rate<Asset>);
+
+#[wasm_bindgen(js_class = Rate)]
+impl WasmRate {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmRate {
+        WasmRate::default()
+    }
+
+    #[wasm_bindgen(js_name = setCredit)]
+    pub fn set_credit(&mut self, asset: &str, quantity: &str) -> Result<(), JsValue> {
+        set(&mut self.0.credit, asset, quantity)
+    }
+
+    #[wasm_bindgen(js_name = setDebit)]
+    pub fn set_debit(&mut self, asset: &str, quantity: &str) -> Result<(), JsValue> {
+        set(&mut self.0.debit, asset, quantity)
+    }
+
+    pub fn credit(&self, asset: &str) -> String {
+        format(self.0.credit.get(asset).copied().unwrap_or(account::fixed_amount(0)))
+    }
+
+    pub fn debit(&self, asset: &str) -> String {
+        format(self.0.debit.get(asset).copied().unwrap_or(account::fixed_amount(0)))
+    }
+
+    /// Charges the buyer a fixed `quantity` of `asset` per exchange,
+    /// replacing any fee already set.
+    #[wasm_bindgen(js_name = setFlatFee)]
+    pub fn set_flat_fee(&mut self, asset: &str, quantity: &str) -> Result<(), JsValue> {
+        self.0.fee = Some(Fee {
+            asset: asset.to_string(),
+            charge: Charge::Flat(parse(quantity)?),
+        });
+        Ok(())
+    }
+
+    /// Charges the buyer `percent` of what it is debited in `asset`,
+    /// replacing any fee already set.
+    #[wasm_bindgen(js_name = setPercentFee)]
+    pub fn set_percent_fee(&mut self, asset: &str, percent: &str) -> Result<(), JsValue> {
+        self.0.fee = Some(Fee {
+            asset: asset.to_string(),
+            charge: Charge::Percent(parse(percent)?),
+        });
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = feeAsset)]
+    pub fn fee_asset(&self) -> Option<String> {
+        self.0.fee.as_ref().map(|fee| fee.asset.clone())
+    }
+}
+
+#[wasm_bindgen(js_name = Tranx)]
+pub struct WasmTranx(Tranx<Asset>);
+
+#[wasm_bindgen(js_class = Tranx)]
+impl WasmTranx {
+    pub fn approved(&self) -> bool {
+        matches!(self.0, Tranx::Approved(..))
+    }
+
+    pub fn buyer(&self) -> Option<WasmAccount> {
+        match &self.0 {
+            Tranx::Approved(buyer, ..) => Some(WasmAccount(buyer.clone())),
+            Tranx::Denied(_) => None,
+        }
+    }
+
+    pub fn seller(&self) -> Option<WasmAccount> {
+        match &self.0 {
+            Tranx::Approved(_, seller, _) => Some(WasmAccount(seller.clone())),
+            Tranx::Denied(_) => None,
+        }
+    }
+
+    #[wasm_bindgen(js_name = feeAccount)]
+    pub fn fee_account(&self) -> Option<WasmAccount> {
+        match &self.0 {
+            Tranx::Approved(_, _, fee_account) => Some(WasmAccount(fee_account.clone())),
+            Tranx::Denied(_) => None,
+        }
+    }
+
+    #[wasm_bindgen(js_name = deficitAssets)]
+    pub fn deficit_assets(&self) -> Box<[JsValue]> {
+        match &self.0 {
+            Tranx::Approved(..) => Box::new([]),
+            Tranx::Denied(deficit) => keys(deficit),
+        }
+    }
+
+    pub fn deficit(&self, asset: &str) -> String {
+        match &self.0 {
+            Tranx::Denied(deficit) => {
+                format(deficit.get(asset).copied().unwrap_or(account::fixed_amount(0)))
+            }
+            Tranx::Approved(..) => format(account::fixed_amount(0)),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn exchange(
+    rate: &WasmRate,
+    quantity: &str,
+    buyer: &WasmAccount,
+    seller: &WasmAccount,
+) -> Result<WasmTranx, JsValue> {
+    Ok(WasmTranx(account::Account::exchange(
+        &rate.0,
+        parse(quantity)?,
+        &buyer.0,
+        &seller.0,
+    )))
+}
+
+/// `exchange`, paying the rate's fee into `fee_account`.
+#[wasm_bindgen(js_name = exchangeWithFee)]
+pub fn exchange_with_fee(
+    rate: &WasmRate,
+    quantity: &str,
+    buyer: &WasmAccount,
+    seller: &WasmAccount,
+    fee_account: &WasmAccount,
+) -> Result<WasmTranx, JsValue> {
+    Ok(WasmTranx(account::Account::exchange_with_fee(
+        &rate.0,
+        parse(quantity)?,
+        &buyer.0,
+        &seller.0,
+        &fee_account.0,
+    )))
+}
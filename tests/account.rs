@@ -1,11 +1,11 @@
 extern crate exgine;
 extern crate uuid;
 
-use exgine::{account::*, asset, hashmap};
+use exgine::{account::*, asset, hashmap, rate::Rate};
 use std::time::Instant;
 use uuid::Uuid;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Fungible {
     Upvote,
     Token,
@@ -13,7 +13,7 @@ pub enum Fungible {
     Point,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub struct Unique {
     id: Uuid,
     created_at: Instant,
@@ -28,14 +28,14 @@ impl Default for Unique {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum NonFungible {
     Coupon(Unique),
     Sticker(Unique),
     Card(Unique),
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum TestAsset {
     Fungible(Fungible),
     NonFungible(NonFungible),
@@ -178,6 +178,31 @@ fn accounts_add_existing_assets() {
     assert_eq!(&lhs + &rhs, res);
 }
 
+#[test]
+fn accounts_add_sub_assign() {
+    let mut lhs = Account::from(hashmap![
+        Asset::Fungible(Fungible::Upvote) => fixed_amount(250),
+        Asset::Fungible(Fungible::Coin) => fixed_amount(800),
+    ]);
+    let rhs = Account::from(hashmap![
+        Asset::Fungible(Fungible::Upvote) => fixed_amount(50),
+        Asset::Fungible(Fungible::Token) => fixed_amount(200),
+    ]);
+    lhs += &rhs;
+    assert_eq!(lhs, Account::from(hashmap![
+        Asset::Fungible(Fungible::Upvote) => fixed_amount(300),
+        Asset::Fungible(Fungible::Token) => fixed_amount(200),
+        Asset::Fungible(Fungible::Coin) => fixed_amount(800),
+    ]));
+    lhs -= &rhs;
+    lhs -= &rhs;
+    assert_eq!(lhs, Account::from(hashmap![
+        Asset::Fungible(Fungible::Upvote) => fixed_amount(200),
+        Asset::Fungible(Fungible::Token) => fixed_amount(-200),
+        Asset::Fungible(Fungible::Coin) => fixed_amount(800),
+    ]));
+}
+
 #[test]
 fn accounts_add_missing_assets() {
     let nft_coupon_a = NonFungible::Coupon(Unique::default());
@@ -204,3 +229,53 @@ fn accounts_add_missing_assets() {
     ]);
     assert_eq!(&lhs + &rhs, res);
 }
+
+#[test]
+#[cfg(feature = "ordered")]
+fn accounts_ordered_assets() {
+    let lhs = Account::from(hashmap![
+        Asset::Fungible(Fungible::Point) => fixed_amount(100),
+        Asset::Fungible(Fungible::Upvote) => fixed_amount(500),
+        Asset::Fungible(Fungible::Coin) => fixed_amount(800),
+    ]);
+    let rhs = Account::from(hashmap![
+        Asset::Fungible(Fungible::Token) => fixed_amount(10000),
+        Asset::Fungible(Fungible::Coin) => fixed_amount(700),
+    ]);
+    let res = &lhs + &rhs;
+    let assets: Vec<&Asset> = res.assets().keys().collect();
+    assert_eq!(
+        assets,
+        vec![
+            &Asset::Fungible(Fungible::Upvote),
+            &Asset::Fungible(Fungible::Token),
+            &Asset::Fungible(Fungible::Coin),
+            &Asset::Fungible(Fungible::Point),
+        ]
+    );
+    assert_eq!(format!("{:?}", res), format!("{:?}", &rhs + &lhs));
+}
+
+// Assets need not implement `Debug`, even in debug builds.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+struct Opaque(u8);
+
+impl asset::Asset for Opaque {}
+
+#[test]
+fn accounts_exchange_opaque_assets() {
+    let rate = Rate {
+        credit: hashmap![Opaque(0) => fixed_amount(1)],
+        debit: hashmap![Opaque(1) => fixed_amount(2)],
+        fee: None,
+    };
+    let buyer = Account::from(hashmap![Opaque(1) => fixed_amount(4)]);
+    let seller = Account::from(hashmap![Opaque(0) => fixed_amount(2)]);
+    match Account::exchange(&rate, fixed_amount(2), &buyer, &seller) {
+        Tranx::Approved(buyer, seller, _) => {
+            assert_eq!(buyer.quantity(&Opaque(0)), fixed_amount(2));
+            assert_eq!(seller.quantity(&Opaque(1)), fixed_amount(4));
+        }
+        Tranx::Denied(_) => panic!("expected approval"),
+    }
+}
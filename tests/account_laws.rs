@@ -0,0 +1,191 @@
+#![cfg(feature = "arbitrary")]
+
+extern crate exgine;
+extern crate proptest;
+
+mod common;
+
+use common::Resource;
+use exgine::{
+    account::*,
+    dense::{DenseAccount, DenseTranx, Interner},
+    rate::{Charge, Fee, Rate},
+};
+use proptest::{collection, prelude::*};
+
+impl Arbitrary for Resource {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Resource>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(Resource::Energy),
+            Just(Resource::Labor),
+            Just(Resource::Ore),
+            Just(Resource::Tool),
+        ]
+        .boxed()
+    }
+}
+
+fn integer() -> impl Strategy<Value = Quantity> {
+    (-64..64).prop_map(fixed_amount)
+}
+
+fn fee() -> impl Strategy<Value = Fee<Resource>> {
+    let charge = prop_oneof![
+        (0..100).prop_map(|amount| Charge::Flat(fixed_amount(amount))),
+        (0..100).prop_map(|percent| Charge::Percent(fixed_amount(percent))),
+    ];
+    (any::<Resource>(), charge).prop_map(|(asset, charge)| Fee { asset, charge })
+}
+
+fn rate() -> impl Strategy<Value = Rate<Resource>> {
+    let side = || collection::vec((any::<Resource>(), (0..100).prop_map(fixed_amount)), 0..4);
+    (side(), side(), proptest::option::of(fee())).prop_map(|(credit, debit, fee)| Rate {
+        credit: credit.into_iter().collect(),
+        debit: debit.into_iter().collect(),
+        fee,
+    })
+}
+
+// An arbitrary account with some credit lines and whichever holds it covers.
+fn limited() -> impl Strategy<Value = Account<Resource>> {
+    let lines = || collection::vec((any::<Resource>(), (0..100).prop_map(fixed_amount)), 0..3);
+    (any::<Account<Resource>>(), lines(), lines()).prop_map(|(account, credit, holds)| {
+        let mut account = credit.into_iter().fold(account, |account, (asset, limit)| {
+            account.with_credit(asset, limit)
+        });
+        for (asset, amount) in holds {
+            let _ = account.hold(&asset, amount);
+        }
+        account
+    })
+}
+
+proptest! {
+    #[test]
+    fn add_commutes(a: Account<Resource>, b: Account<Resource>) {
+        prop_assert_eq!(&a + &b, &b + &a);
+    }
+
+    #[test]
+    fn add_associates(a: Account<Resource>, b: Account<Resource>, c: Account<Resource>) {
+        prop_assert_eq!(&(&a + &b) + &c, &a + &(&b + &c));
+    }
+
+    #[test]
+    fn sub_undoes_add(a: Account<Resource>, b: Account<Resource>) {
+        prop_assert_eq!(&(&a + &b) - &b, a);
+    }
+
+    #[test]
+    fn assign_matches_ops(a: Account<Resource>, b: Account<Resource>) {
+        let mut sum = a.clone();
+        sum += &b;
+        prop_assert_eq!(&sum, &(&a + &b));
+        sum -= &b;
+        prop_assert_eq!(sum, a);
+    }
+
+    #[test]
+    fn mul_distributes_over_add(a: Account<Resource>, b: Account<Resource>, q in integer()) {
+        prop_assert_eq!(&(&a + &b) * q, &(&a * q) + &(&b * q));
+    }
+
+    #[test]
+    fn mul_distributes_over_quantities(a: Account<Resource>, q in integer(), r in integer()) {
+        let (Quantity::Amount(q_amount), Quantity::Amount(r_amount)) = (q, r);
+        prop_assert_eq!(&a * Quantity::Amount(q_amount + r_amount), &(&a * q) + &(&a * r));
+    }
+
+    #[test]
+    fn exchange_conserves_supply(
+        rate in rate(),
+        quantity in (0..1000).prop_map(fixed_amount),
+        buyer: Account<Resource>,
+        seller: Account<Resource>,
+        fees: Account<Resource>,
+    ) {
+        let before = &(&buyer + &seller) + &fees;
+        match Account::exchange_with_fee(&rate, quantity, &buyer, &seller, &fees) {
+            Tranx::Approved(buyer, seller, fees) => {
+                prop_assert_eq!(&(&buyer + &seller) + &fees, before)
+            }
+            Tranx::Denied(deficit) => prop_assert!(!deficit.is_empty()),
+        }
+    }
+
+    #[test]
+    fn quote_matches_exchange(
+        rate in rate(),
+        quantity in (0..1000).prop_map(fixed_amount),
+        buyer: Account<Resource>,
+        seller: Account<Resource>,
+    ) {
+        let quote = Account::quote(&rate, quantity, &buyer, &seller);
+        prop_assert_eq!(Account::can_afford(&rate, quantity, &buyer, &seller), quote.approved());
+        match Account::exchange(&rate, quantity, &buyer, &seller) {
+            Tranx::Approved(buyer, seller, fees) => {
+                prop_assert!(quote.approved());
+                prop_assert_eq!(Account::from(quote.fees), fees);
+                for (asset, quantity) in quote.buyer {
+                    prop_assert_eq!(buyer.quantity(&asset), quantity);
+                }
+                for (asset, quantity) in quote.seller {
+                    prop_assert_eq!(seller.quantity(&asset), quantity);
+                }
+            }
+            Tranx::Denied(deficit) => prop_assert_eq!(quote.deficit, deficit),
+        }
+    }
+
+    #[test]
+    fn exchange_in_place_matches_exchange(
+        rate in rate(),
+        quantity in (0..1000).prop_map(fixed_amount),
+        buyer: Account<Resource>,
+        seller: Account<Resource>,
+        fees: Account<Resource>,
+    ) {
+        let tranx = Account::exchange_with_fee(&rate, quantity, &buyer, &seller, &fees);
+        let (mut buyer, mut seller, mut fees) = (buyer, seller, fees);
+        let applied = Account::exchange_in_place(&rate, quantity, &mut buyer, &mut seller, &mut fees);
+        match (tranx, applied) {
+            (Tranx::Approved(b, s, f), Ok(())) => {
+                prop_assert_eq!(buyer, b);
+                prop_assert_eq!(seller, s);
+                prop_assert_eq!(fees, f);
+            }
+            (Tranx::Denied(deficit), Err(applied)) => prop_assert_eq!(deficit, applied),
+            _ => prop_assert!(false, "exchange and exchange_in_place disagree"),
+        }
+    }
+
+    #[test]
+    fn dense_exchange_matches_exchange(
+        rate in rate(),
+        quantity in (0..1000).prop_map(fixed_amount),
+        buyer in limited(),
+        seller in limited(),
+    ) {
+        let mut interner = Interner::new();
+        let dense_rate = interner.rate(&rate);
+        let (dense_buyer, dense_seller) = (interner.account(&buyer), interner.account(&seller));
+        let dense = DenseAccount::exchange(&dense_rate, quantity, &dense_buyer, &dense_seller);
+        match (Account::exchange(&rate, quantity, &buyer, &seller), dense) {
+            (Tranx::Approved(b, s, f), DenseTranx::Approved(db, ds, df)) => {
+                for (dense, sparse) in [(&db, &b), (&ds, &s), (&df, &f)] {
+                    let dense = interner.sparse(dense);
+                    prop_assert_eq!(&dense, sparse);
+                    prop_assert_eq!(dense.credit_lines(), sparse.credit_lines());
+                    prop_assert_eq!(dense.holds(), sparse.holds());
+                }
+            }
+            (Tranx::Denied(deficit), DenseTranx::Denied(dense)) => {
+                prop_assert_eq!(interner.deficit(&dense), deficit)
+            }
+            _ => prop_assert!(false, "dense and sparse exchanges disagree"),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+extern crate exgine;
+
+mod common;
+
+use common::Resource;
+use exgine::{
+    account::{fixed_amount, Account, Quantity, Tranx},
+    batch::{Batch, Order},
+    hashmap, invariant,
+    map::Map,
+    rate::{Charge, Fee, Rate},
+};
+
+const AGENTS: usize = 64;
+const MISSIONS: usize = 4;
+const TREASURY: usize = AGENTS + MISSIONS;
+
+fn rates() -> Vec<Rate<Resource>> {
+    vec![
+        Rate {
+            credit: hashmap![Resource::MissionTime => fixed_amount(1)],
+            debit: hashmap![Resource::Battery => fixed_amount(20)],
+            fee: None,
+        },
+        Rate {
+            credit: hashmap![Resource::MissionTime => fixed_amount(1)],
+            debit: hashmap![Resource::Trust => fixed_amount(1)],
+            fee: Some(Fee {
+                asset: Resource::Trust,
+                charge: Charge::Flat(fixed_amount(1)),
+            }),
+        },
+    ]
+}
+
+// Agents, then missions with little MissionTime so later orders get denied,
+// then the treasury collecting fees.
+fn registry() -> Vec<Account<Resource>> {
+    let mut accounts = Vec::new();
+    for agent in 0..AGENTS {
+        accounts.push(Account::from(hashmap![
+            Resource::Trust => fixed_amount(agent as i32 % 7),
+            Resource::Battery => fixed_amount(agent as i32 * 5),
+        ]));
+    }
+    for _ in 0..MISSIONS {
+        accounts.push(Account::from(
+            hashmap![Resource::MissionTime => fixed_amount(40)],
+        ));
+    }
+    accounts.push(Account::default());
+    accounts
+}
+
+fn batch(rates: &[Rate<Resource>]) -> Batch<'_, Resource> {
+    let mut batch = Batch::new();
+    for tick in 0..3 {
+        for agent in 0..AGENTS {
+            batch.push(Order {
+                rate: &rates[(agent + tick) % rates.len()],
+                quantity: fixed_amount(1 + tick as i32),
+                buyer: agent,
+                seller: AGENTS + (agent * 7 + tick) % MISSIONS,
+                fee_account: TREASURY,
+            });
+        }
+    }
+    batch
+}
+
+// The reference settles each order in push order through the functional
+// `exchange_with_fee`, a separate path from the in-place one `Batch` uses.
+fn sequential(
+    batch: &Batch<Resource>,
+    accounts: &mut [Account<Resource>],
+) -> Vec<Result<(), Map<Resource, Quantity>>> {
+    batch
+        .orders()
+        .iter()
+        .map(|order| {
+            match Account::exchange_with_fee(
+                order.rate,
+                order.quantity,
+                &accounts[order.buyer],
+                &accounts[order.seller],
+                &accounts[order.fee_account],
+            ) {
+                Tranx::Approved(buyer, seller, fees) => {
+                    accounts[order.buyer] = buyer;
+                    accounts[order.seller] = seller;
+                    accounts[order.fee_account] = fees;
+                    Ok(())
+                }
+                Tranx::Denied(deficit) => Err(deficit),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn batch_waves_touch_disjoint_accounts() {
+    let rates = rates();
+    let batch = batch(&rates);
+    assert_eq!(batch.orders().len(), 3 * AGENTS);
+    assert_eq!(
+        batch.waves().iter().map(Vec::len).sum::<usize>(),
+        batch.orders().len()
+    );
+    for wave in batch.waves() {
+        let mut touched = Vec::new();
+        for &index in wave {
+            let order = &batch.orders()[index];
+            touched.push(order.buyer);
+            touched.push(order.seller);
+            if order.rate.fee.is_some() {
+                touched.push(order.fee_account);
+            }
+        }
+        let count = touched.len();
+        touched.sort_unstable();
+        touched.dedup();
+        assert_eq!(touched.len(), count);
+    }
+}
+
+#[test]
+fn batch_matches_sequential() {
+    let rates = rates();
+    let batch = batch(&rates);
+    let mut expected = registry();
+    let expected_results = sequential(&batch, &mut expected);
+    assert!(expected_results.iter().any(Result::is_ok));
+    assert!(expected_results.iter().any(Result::is_err));
+
+    let mut accounts = registry();
+    assert_eq!(batch.execute(&mut accounts), expected_results);
+    assert_eq!(accounts, expected);
+
+    // Agent 5 buys from mission 3 with Trust on the first tick, then lacks
+    // the Battery for its second order and the Trust for its third.
+    assert_eq!(batch.orders()[5].seller, AGENTS + 3);
+    assert_eq!(expected_results[5], Ok(()));
+    assert_eq!(
+        expected_results[AGENTS + 5],
+        Err(hashmap![Resource::Battery => fixed_amount(-15)])
+    );
+    assert_eq!(
+        expected_results[2 * AGENTS + 5],
+        Err(hashmap![Resource::Trust => fixed_amount(-1)])
+    );
+    assert_eq!(
+        accounts[5],
+        Account::from(hashmap![
+            Resource::MissionTime => fixed_amount(1),
+            Resource::Trust => fixed_amount(3),
+            Resource::Battery => fixed_amount(25),
+        ])
+    );
+    assert_eq!(invariant::conserved(&registry(), &accounts), Ok(()));
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn batch_parallel_matches_sequential() {
+    let rates = rates();
+    let batch = batch(&rates);
+    let mut expected = registry();
+    let expected_results = sequential(&batch, &mut expected);
+
+    let mut accounts = registry();
+    assert_eq!(batch.execute_parallel(&mut accounts), expected_results);
+    assert_eq!(accounts, expected);
+}
+
+#[test]
+#[should_panic]
+fn batch_rejects_self_exchange() {
+    let rates = rates();
+    let mut batch = Batch::new();
+    batch.push(Order {
+        rate: &rates[0],
+        quantity: fixed_amount(1),
+        buyer: 0,
+        seller: 0,
+        fee_account: TREASURY,
+    });
+}
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "capi", unix))]
+
+extern crate exgine;
+
+use exgine::capi::*;
+use std::{env, env::consts, ffi::CString, path::PathBuf, process::Command, ptr};
+
+// `cargo test` builds the `exgine_capi` example, which the harness links to,
+// into the sibling of this test's directory.
+fn examples() -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().join("examples")
+}
+
+#[test]
+fn capi_header_up_to_date() {
+    assert_eq!(
+        include_str!("../include/exgine.h"),
+        include_str!(concat!(env!("OUT_DIR"), "/exgine.h")),
+        "include/exgine.h is out of date, copy it from OUT_DIR"
+    );
+}
+
+#[test]
+fn capi_harness() {
+    let examples = examples();
+    let library = format!("{}exgine_capi{}", consts::DLL_PREFIX, consts::DLL_SUFFIX);
+    assert!(
+        examples.join(library).exists(),
+        "build the exgine_capi example first, or run every test target"
+    );
+    let harness = examples.join("exgine_capi_harness");
+    let compiler = cc::Build::new()
+        .target(env!("TARGET"))
+        .host(env!("HOST"))
+        .opt_level(0)
+        .cargo_metadata(false)
+        .get_compiler();
+    let built = compiler
+        .to_command()
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/capi/harness.c"))
+        .arg("-I")
+        .arg(env!("OUT_DIR"))
+        .arg("-o")
+        .arg(&harness)
+        .arg("-L")
+        .arg(&examples)
+        .arg("-lexgine_capi")
+        .arg(format!("-Wl,-rpath,{}", examples.display()))
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(built.success(), "tests/capi/harness.c failed to build");
+
+    let run = Command::new(&harness).output().unwrap();
+    assert!(
+        run.status.success(),
+        "tests/capi/harness.c failed at line {}",
+        String::from_utf8_lossy(&run.stdout).trim()
+    );
+}
+
+// Overflow only panics with debug assertions.
+#[cfg(debug_assertions)]
+#[test]
+fn capi_exchange_panic() {
+    let (name, trust) = (
+        CString::new("Overflow").unwrap(),
+        CString::new("Trust").unwrap(),
+    );
+    let huge = exgine_quantity_from_int(1 << 30);
+    unsafe {
+        let (market, rate) = (exgine_market_new(), exgine_rate_new());
+        let (agent, mission) = (exgine_account_new(), exgine_account_new());
+        assert_eq!(
+            exgine_rate_set_debit(rate, trust.as_ptr(), huge),
+            ExgineStatus::Ok
+        );
+        assert_eq!(
+            exgine_market_register_rate(market, name.as_ptr(), rate),
+            ExgineStatus::Ok
+        );
+        let status = exgine_exchange(
+            market,
+            name.as_ptr(),
+            huge,
+            agent,
+            mission,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+        assert_eq!(status, ExgineStatus::Panicked);
+        assert_eq!(exgine_account_quantity(agent, trust.as_ptr()), 0);
+        exgine_rate_free(rate);
+        exgine_market_free(market);
+        exgine_account_free(agent);
+        exgine_account_free(mission);
+    }
+}
@@ -0,0 +1,76 @@
+#include <stdio.h>
+#include <string.h>
+#include "exgine.h"
+
+#define CHECK(cond) do { if (!(cond)) return __LINE__; } while (0)
+
+/* Returns 0 on success, or the line of the first failed check. */
+static int exgine_capi_harness(void) {
+    ExgineAccount *mission = exgine_account_new();
+    ExgineAccount *agent = exgine_account_new();
+    ExgineAccount *treasury = exgine_account_new();
+    ExgineRate *rate = exgine_rate_new();
+    ExgineRate *tolled = exgine_rate_new();
+    ExgineMarket *market = exgine_market_new();
+    ExgineDenial *denial = NULL;
+    ExgineQuantity quantity = 0;
+
+    CHECK(exgine_account_set_quantity(mission, "MissionTime", exgine_quantity_from_int(1000)) == EXGINE_STATUS_OK);
+    CHECK(exgine_account_set_quantity(agent, "Trust", exgine_quantity_from_int(10)) == EXGINE_STATUS_OK);
+    CHECK(exgine_account_set_quantity(agent, NULL, 0) == EXGINE_STATUS_INVALID_ARGUMENT);
+
+    CHECK(exgine_rate_set_credit(rate, "MissionTime", exgine_quantity_from_int(1)) == EXGINE_STATUS_OK);
+    CHECK(exgine_quantity_from_double(2.5, &quantity) == EXGINE_STATUS_OK);
+    CHECK(exgine_quantity_from_double(1e12, &quantity) == EXGINE_STATUS_INVALID_ARGUMENT);
+    CHECK(exgine_quantity_from_double(2.5, NULL) == EXGINE_STATUS_INVALID_ARGUMENT);
+    CHECK(exgine_rate_set_debit(rate, "Trust", quantity) == EXGINE_STATUS_OK);
+    CHECK(exgine_market_register_rate(market, "MissionTimeWithTrust", rate) == EXGINE_STATUS_OK);
+    exgine_rate_free(rate);
+
+    CHECK(exgine_exchange(market, "MissionTimeWithTrust", exgine_quantity_from_int(2), agent, mission, NULL, &denial) == EXGINE_STATUS_OK);
+    CHECK(denial == NULL);
+    CHECK(exgine_account_quantity(agent, "MissionTime") == exgine_quantity_from_int(2));
+    CHECK(exgine_account_quantity(agent, "Trust") == exgine_quantity_from_int(5));
+    CHECK(exgine_account_quantity(mission, "Trust") == exgine_quantity_from_int(5));
+    CHECK(exgine_account_quantity(mission, "MissionTime") == exgine_quantity_from_int(998));
+
+    CHECK(exgine_exchange(market, "MissionTimeWithTrust", exgine_quantity_from_int(3), agent, mission, NULL, &denial) == EXGINE_STATUS_DENIED);
+    CHECK(denial != NULL);
+    CHECK(exgine_denial_len(denial) == 1);
+    CHECK(strcmp(exgine_denial_asset(denial, 0), "Trust") == 0);
+    CHECK(exgine_quantity_to_double(exgine_denial_quantity(denial, 0)) == -2.5);
+    CHECK(exgine_denial_asset(denial, 1) == NULL);
+    exgine_denial_free(denial);
+    CHECK(exgine_account_quantity(agent, "Trust") == exgine_quantity_from_int(5));
+
+    CHECK(exgine_exchange(market, "MissionTimeWithResource", exgine_quantity_from_int(1), agent, mission, NULL, NULL) == EXGINE_STATUS_UNKNOWN_RATE);
+    CHECK(exgine_exchange(market, "MissionTimeWithTrust", exgine_quantity_from_int(1), agent, agent, NULL, NULL) == EXGINE_STATUS_INVALID_ARGUMENT);
+
+    CHECK(exgine_rate_set_credit(tolled, "MissionTime", exgine_quantity_from_int(1)) == EXGINE_STATUS_OK);
+    CHECK(exgine_rate_set_debit(tolled, "Trust", exgine_quantity_from_int(1)) == EXGINE_STATUS_OK);
+    CHECK(exgine_rate_set_fee(tolled, "Trust", EXGINE_CHARGE_PERCENT, exgine_quantity_from_int(50)) == EXGINE_STATUS_OK);
+    CHECK(exgine_rate_set_fee(tolled, NULL, EXGINE_CHARGE_FLAT, 0) == EXGINE_STATUS_INVALID_ARGUMENT);
+    CHECK(exgine_market_register_rate(market, "MissionTimeWithFee", tolled) == EXGINE_STATUS_OK);
+    exgine_rate_free(tolled);
+
+    CHECK(exgine_exchange(market, "MissionTimeWithFee", exgine_quantity_from_int(2), agent, mission, NULL, NULL) == EXGINE_STATUS_INVALID_ARGUMENT);
+    CHECK(exgine_exchange(market, "MissionTimeWithFee", exgine_quantity_from_int(2), agent, mission, agent, NULL) == EXGINE_STATUS_INVALID_ARGUMENT);
+    CHECK(exgine_exchange(market, "MissionTimeWithFee", exgine_quantity_from_int(2), agent, mission, treasury, NULL) == EXGINE_STATUS_OK);
+    CHECK(exgine_account_quantity(agent, "Trust") == exgine_quantity_from_int(2));
+    CHECK(exgine_account_quantity(mission, "Trust") == exgine_quantity_from_int(7));
+    CHECK(exgine_account_quantity(treasury, "Trust") == exgine_quantity_from_int(1));
+
+    exgine_market_free(market);
+    exgine_account_free(treasury);
+    exgine_account_free(agent);
+    exgine_account_free(mission);
+    return 0;
+}
+
+int main(void) {
+    int line = exgine_capi_harness();
+    if (line != 0) {
+        printf("%d\n", line);
+    }
+    return line != 0;
+}
@@ -0,0 +1,34 @@
+// Shared by several test crates, each of which uses only part of it.
+#![allow(dead_code)]
+
+use exgine::{
+    account::{fixed_amount, Account},
+    asset,
+    map::Map,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Resource {
+    MissionTime,
+    Trust,
+    Battery,
+    Energy,
+    Labor,
+    Ore,
+    Tool,
+}
+
+impl asset::Asset for Resource {}
+
+pub fn account(amounts: &[(Resource, i32)]) -> Account<Resource> {
+    Account::from(
+        amounts
+            .iter()
+            .map(|&(asset, amount)| (asset, fixed_amount(amount)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+pub fn mission() -> Account<Resource> {
+    account(&[(Resource::MissionTime, 1000)])
+}
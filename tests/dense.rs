@@ -0,0 +1,156 @@
+extern crate exgine;
+
+mod common;
+
+use common::{mission, Resource};
+use exgine::{
+    account::{fixed_amount, Account, Tranx},
+    dense::{DenseAccount, DenseTranx, Interner},
+    hashmap,
+    rate::{Charge, Fee, Rate},
+};
+
+fn rates() -> Vec<Rate<Resource>> {
+    vec![
+        Rate {
+            credit: hashmap![Resource::MissionTime => fixed_amount(1)],
+            debit: hashmap![Resource::Battery => fixed_amount(20)],
+            fee: None,
+        },
+        Rate {
+            credit: hashmap![Resource::MissionTime => fixed_amount(1)],
+            debit: hashmap![Resource::Trust => fixed_amount(1)],
+            fee: Some(Fee {
+                asset: Resource::Trust,
+                charge: Charge::Percent(fixed_amount(25)),
+            }),
+        },
+    ]
+}
+
+fn agent() -> Account<Resource> {
+    common::account(&[(Resource::Trust, 100), (Resource::Battery, 1000)])
+}
+
+#[test]
+fn dense_roundtrip() {
+    let mut interner = Interner::new();
+    let dense = interner.account(&agent());
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.sparse(&dense), agent());
+    let index = interner.index(&Resource::Trust).unwrap();
+    assert_eq!(dense.quantity(index), fixed_amount(100));
+    assert_eq!(interner.asset(index), Some(&Resource::Trust));
+    assert_eq!(dense.quantity(7), fixed_amount(0));
+    assert_eq!(interner.intern(&Resource::MissionTime), 2);
+}
+
+#[test]
+fn dense_credit_and_holds() {
+    let mut agent = agent().with_credit(Resource::Trust, fixed_amount(10));
+    assert!(agent.hold(&Resource::Battery, fixed_amount(990)).is_ok());
+    let mut interner = Interner::new();
+    let dense = interner.account(&agent);
+    let (trust, battery) = (
+        interner.index(&Resource::Trust).unwrap(),
+        interner.index(&Resource::Battery).unwrap(),
+    );
+    assert_eq!(dense.credit(trust), fixed_amount(10));
+    assert_eq!(dense.held(battery), fixed_amount(990));
+    let sparse = interner.sparse(&dense);
+    assert_eq!(sparse.credit_lines(), agent.credit_lines());
+    assert_eq!(sparse.holds(), agent.holds());
+
+    // Only 10 Battery is available, half of what the first rate debits.
+    let rate = interner.rate(&rates()[0]);
+    let mission = interner.account(&mission());
+    match DenseAccount::exchange(&rate, fixed_amount(1), &dense, &mission) {
+        DenseTranx::Denied(deficit) => assert_eq!(
+            interner.deficit(&deficit),
+            hashmap![Resource::Battery => fixed_amount(-10)]
+        ),
+        _ => panic!("exchange approved"),
+    }
+    // 100 Trust and 10 on credit cover 88 units at 1.25 Trust each.
+    let rate = interner.rate(&rates()[1]);
+    match DenseAccount::exchange(&rate, fixed_amount(88), &dense, &mission) {
+        DenseTranx::Approved(agent, ..) => {
+            assert_eq!(agent.quantity(trust), fixed_amount(-10))
+        }
+        _ => panic!("exchange denied"),
+    }
+}
+
+#[test]
+fn dense_lifetime_matches_sparse() {
+    let rates = rates();
+    let mut interner = Interner::new();
+    let dense_rates: Vec<_> = rates.iter().map(|rate| interner.rate(rate)).collect();
+
+    let (mut agent, mut mission, mut fees) = (agent(), mission(), Account::default());
+    let (mut dense_agent, mut dense_mission, mut dense_fees) = (
+        interner.account(&agent),
+        interner.account(&mission),
+        DenseAccount::default(),
+    );
+    let mut ticks = 0;
+    'tick: loop {
+        for (rate, dense_rate) in rates.iter().zip(&dense_rates) {
+            let sparse = Account::exchange_with_fee(rate, fixed_amount(1), &agent, &mission, &fees);
+            let dense = DenseAccount::exchange_with_fee(
+                dense_rate,
+                fixed_amount(1),
+                &dense_agent,
+                &dense_mission,
+                &dense_fees,
+            );
+            match (sparse, dense) {
+                (Tranx::Approved(b, s, f), DenseTranx::Approved(db, ds, df)) => {
+                    assert_eq!(interner.sparse(&db), b);
+                    assert_eq!(interner.sparse(&ds), s);
+                    assert_eq!(interner.sparse(&df), f);
+                    agent = b;
+                    mission = s;
+                    fees = f;
+                    dense_agent = db;
+                    dense_mission = ds;
+                    dense_fees = df;
+                    ticks += 1;
+                    continue 'tick;
+                }
+                (Tranx::Denied(deficit), DenseTranx::Denied(dense_deficit)) => {
+                    assert_eq!(interner.deficit(&dense_deficit), deficit);
+                }
+                _ => panic!("dense and sparse exchanges disagree"),
+            }
+        }
+        break;
+    }
+    // 50 ticks on Battery, then 80 on Trust at 1.25 Trust each.
+    assert_eq!(ticks, 130);
+    assert_eq!(
+        dense_fees.quantity(interner.index(&Resource::Trust).unwrap()),
+        fixed_amount(20)
+    );
+}
+
+#[test]
+fn dense_negative_fee_denied() {
+    let rate = Rate {
+        fee: Some(Fee {
+            asset: Resource::Trust,
+            charge: Charge::Flat(fixed_amount(-3)),
+        }),
+        ..rates()[1].clone()
+    };
+    let mut interner = Interner::new();
+    let dense_rate = interner.rate(&rate);
+    let (agent, mission) = (interner.account(&agent()), interner.account(&mission()));
+    match DenseAccount::exchange(&dense_rate, fixed_amount(1), &agent, &mission) {
+        DenseTranx::Denied(deficit) => assert_eq!(
+            interner.deficit(&deficit),
+            hashmap![Resource::Trust => fixed_amount(-3)]
+        ),
+        _ => panic!("exchange approved"),
+    }
+}
@@ -0,0 +1,98 @@
+extern crate exgine;
+
+mod common;
+
+use common::{mission, Resource};
+use exgine::{
+    account::{fixed_amount, Account, Tranx},
+    escrow::{Escrow, Preparation, Settlement},
+    hashmap, invariant,
+    rate::{Charge, Fee, Rate},
+};
+
+fn rate() -> Rate<Resource> {
+    Rate {
+        credit: hashmap![Resource::MissionTime => fixed_amount(1)],
+        debit: hashmap![Resource::Trust => fixed_amount(10)],
+        fee: Some(Fee {
+            asset: Resource::Trust,
+            charge: Charge::Percent(fixed_amount(5)),
+        }),
+    }
+}
+
+fn agent() -> Account<Resource> {
+    common::account(&[(Resource::Trust, 100)])
+}
+
+fn prepared(expires: u64) -> (Escrow<Resource>, Account<Resource>, Account<Resource>) {
+    match Escrow::prepare(&rate(), fixed_amount(4), &agent(), &mission(), expires) {
+        Preparation::Prepared(escrow, buyer, seller) => (escrow, buyer, seller),
+        Preparation::Denied(_) => panic!("prepare denied"),
+    }
+}
+
+#[test]
+fn escrow_commit_matches_exchange() {
+    let (escrow, buyer, seller) = prepared(10);
+    assert_eq!(buyer.quantity(&Resource::Trust), fixed_amount(58));
+    assert_eq!(seller.quantity(&Resource::MissionTime), fixed_amount(996));
+    assert!(
+        invariant::conserved([&agent(), &mission()], [&buyer, &seller, &escrow.account()]).is_ok()
+    );
+
+    let treasury = Account::default();
+    let exchanged =
+        Account::exchange_with_fee(&rate(), fixed_amount(4), &agent(), &mission(), &treasury);
+    match (escrow.commit(9, &buyer, &seller, &treasury), exchanged) {
+        (Settlement::Committed(buyer, seller, fees), Tranx::Approved(b, s, f)) => {
+            assert_eq!(buyer, b);
+            assert_eq!(seller, s);
+            assert_eq!(fees, f);
+        }
+        _ => panic!("commit and exchange disagree"),
+    }
+}
+
+#[test]
+fn escrow_refund_and_expiry() {
+    let (escrow, buyer, seller) = prepared(10);
+    assert!(!escrow.expired(9));
+    assert!(escrow.expired(10));
+    match escrow.clone().refund(&buyer, &seller) {
+        Settlement::Refunded(buyer, seller) => {
+            assert_eq!(buyer, agent());
+            assert_eq!(seller, mission());
+        }
+        _ => panic!("refund committed"),
+    }
+    match escrow.commit(10, &buyer, &seller, &Account::default()) {
+        Settlement::Refunded(buyer, seller) => {
+            assert_eq!(buyer, agent());
+            assert_eq!(seller, mission());
+        }
+        _ => panic!("expired escrow committed"),
+    }
+}
+
+#[test]
+fn escrow_prepare_denied() {
+    match Escrow::prepare(&rate(), fixed_amount(10), &agent(), &mission(), 10) {
+        Preparation::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Resource::Trust => fixed_amount(-5)])
+        }
+        _ => panic!("prepare approved"),
+    }
+}
+
+#[test]
+fn escrow_prepare_seller_hold() {
+    let mut mission = mission();
+    assert!(mission.hold(&Resource::MissionTime, fixed_amount(997)).is_ok());
+    match Escrow::prepare(&rate(), fixed_amount(4), &agent(), &mission, 10) {
+        Preparation::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Resource::MissionTime => fixed_amount(-1)])
+        }
+        _ => panic!("prepare approved"),
+    }
+}
@@ -0,0 +1,62 @@
+extern crate exgine;
+
+mod common;
+
+use common::Resource;
+use exgine::{
+    account::{fixed_amount, Account, Tranx},
+    hashmap, invariant,
+    rate::Rate,
+};
+
+fn registry() -> Vec<Account<Resource>> {
+    vec![
+        Account::from(hashmap![Resource::Energy => fixed_amount(100)]),
+        Account::from(hashmap![Resource::Labor => fixed_amount(50)]),
+        Account::from(hashmap![Resource::Ore => fixed_amount(10)]),
+    ]
+}
+
+#[test]
+fn registry_conserved_across_exchanges() {
+    let rate = Rate {
+        credit: hashmap![Resource::Labor => fixed_amount(2)],
+        debit: hashmap![Resource::Energy => fixed_amount(3)],
+        fee: None,
+    };
+    let before = registry();
+    let mut after = before.clone();
+    for _ in 0..5 {
+        match Account::exchange(&rate, fixed_amount(4), &after[0], &after[1]) {
+            Tranx::Approved(buyer, seller, _) => {
+                after[0] = buyer;
+                after[1] = seller;
+            }
+            Tranx::Denied(_) => panic!("exchange denied"),
+        }
+    }
+    assert_eq!(invariant::conserved(&before, &after), Ok(()));
+    assert_eq!(
+        invariant::total(&after),
+        Account::from(hashmap![
+            Resource::Energy => fixed_amount(100),
+            Resource::Labor => fixed_amount(50),
+            Resource::Ore => fixed_amount(10),
+        ])
+    );
+}
+
+#[test]
+fn registry_reports_created_and_destroyed_assets() {
+    let before = registry();
+    let mut after = before.clone();
+    after[1] = Account::from(hashmap![Resource::Labor => fixed_amount(45)]);
+    after.push(Account::from(hashmap![Resource::Ore => fixed_amount(1)]));
+    assert_eq!(
+        invariant::conserved(&before, &after),
+        Err(hashmap![
+            Resource::Labor => fixed_amount(-5),
+            Resource::Ore => fixed_amount(1),
+        ])
+    );
+}
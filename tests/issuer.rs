@@ -0,0 +1,158 @@
+extern crate exgine;
+
+mod common;
+
+use common::Resource;
+use exgine::{
+    account::{fixed_amount, Account, Quantity, I32F32},
+    hashmap, invariant,
+    issuer::{Issuance, Issuer},
+};
+
+fn minted(
+    issuer: &Issuer<Resource>,
+    amount: i32,
+    account: &Account<Resource>,
+) -> (Issuer<Resource>, Account<Resource>) {
+    match issuer.mint(fixed_amount(amount), account) {
+        Issuance::Approved(issuer, account) => (issuer, account),
+        Issuance::Denied(_) => panic!("mint denied"),
+    }
+}
+
+#[test]
+fn issuer_mint_tracks_supply() {
+    let issuer = Issuer::new(Resource::MissionTime, None);
+    let (issuer, mission) = minted(&issuer, 1000, &Account::default());
+    let agent = Account::from(hashmap![Resource::Trust => fixed_amount(10)]);
+    let (issuer, agent) = minted(&issuer, 5, &agent);
+
+    assert_eq!(issuer.supply(), fixed_amount(1005));
+    assert_eq!(
+        agent,
+        Account::from(hashmap![
+            Resource::MissionTime => fixed_amount(5),
+            Resource::Trust => fixed_amount(10),
+        ])
+    );
+    assert_eq!(
+        invariant::total(&[mission, agent]).quantity(&Resource::MissionTime),
+        issuer.supply()
+    );
+}
+
+#[test]
+fn issuer_mint_over_cap_denied() {
+    let issuer = Issuer::new(Resource::MissionTime, Some(fixed_amount(100)));
+    let (issuer, mission) = minted(&issuer, 80, &Account::default());
+    match issuer.mint(fixed_amount(30), &mission) {
+        Issuance::Denied(excess) => {
+            assert_eq!(excess, hashmap![Resource::MissionTime => fixed_amount(-10)])
+        }
+        _ => panic!("mint approved"),
+    }
+    let (issuer, _) = minted(&issuer, 20, &mission);
+    assert_eq!(issuer.supply(), fixed_amount(100));
+}
+
+#[test]
+fn issuer_burn() {
+    let issuer = Issuer::new(Resource::MissionTime, Some(fixed_amount(100)));
+    let (issuer, mission) = minted(&issuer, 100, &Account::default());
+    let (issuer, mission) = match issuer.burn(fixed_amount(40), &mission) {
+        Issuance::Approved(issuer, mission) => (issuer, mission),
+        Issuance::Denied(_) => panic!("burn denied"),
+    };
+    assert_eq!(issuer.supply(), fixed_amount(60));
+    assert_eq!(mission.quantity(&Resource::MissionTime), fixed_amount(60));
+
+    match issuer.burn(fixed_amount(70), &mission) {
+        Issuance::Denied(deficit) => {
+            assert_eq!(
+                deficit,
+                hashmap![Resource::MissionTime => fixed_amount(-10)]
+            )
+        }
+        _ => panic!("burn approved"),
+    }
+    // Burning frees room under the cap again.
+    minted(&issuer, 40, &mission);
+}
+
+#[test]
+fn issuer_non_positive_denied() {
+    let issuer = Issuer::new(Resource::MissionTime, None);
+    let (issuer, mission) = minted(&issuer, 100, &Account::default());
+    for amount in [0, -10] {
+        let denied = hashmap![Resource::MissionTime => fixed_amount(amount)];
+        match issuer.mint(fixed_amount(amount), &mission) {
+            Issuance::Denied(deficit) => assert_eq!(deficit, denied),
+            _ => panic!("mint approved"),
+        }
+        match issuer.burn(fixed_amount(amount), &mission) {
+            Issuance::Denied(deficit) => assert_eq!(deficit, denied),
+            _ => panic!("burn approved"),
+        }
+    }
+    assert_eq!(issuer.supply(), fixed_amount(100));
+}
+
+#[test]
+fn issuer_burn_respects_holds_and_credit() {
+    let issuer = Issuer::new(Resource::MissionTime, None);
+    let (issuer, mission) = minted(&issuer, 100, &Account::default());
+    let mut mission = mission.with_credit(Resource::MissionTime, fixed_amount(20));
+    assert!(mission.hold(&Resource::MissionTime, fixed_amount(50)).is_ok());
+
+    match issuer.burn(fixed_amount(71), &mission) {
+        Issuance::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Resource::MissionTime => fixed_amount(-1)])
+        }
+        _ => panic!("burn approved"),
+    }
+    match issuer.burn(fixed_amount(70), &mission) {
+        Issuance::Approved(issuer, mission) => {
+            assert_eq!(issuer.supply(), fixed_amount(30));
+            assert_eq!(mission.quantity(&Resource::MissionTime), fixed_amount(30));
+            assert_eq!(mission.held(&Resource::MissionTime), fixed_amount(50));
+        }
+        _ => panic!("burn denied"),
+    }
+}
+
+#[test]
+fn issuer_burn_beyond_supply_denied() {
+    let issuer = Issuer::new(Resource::MissionTime, None);
+    let (issuer, mission) = minted(&issuer, 10, &Account::default());
+    let mission = &mission + &common::account(&[(Resource::MissionTime, 90)]);
+    let mission = mission.with_credit(Resource::MissionTime, fixed_amount(50));
+    match issuer.burn(fixed_amount(11), &mission) {
+        Issuance::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Resource::MissionTime => fixed_amount(-1)])
+        }
+        _ => panic!("burn approved"),
+    }
+    match issuer.burn(fixed_amount(10), &mission) {
+        Issuance::Approved(issuer, _) => assert_eq!(issuer.supply(), fixed_amount(0)),
+        _ => panic!("burn denied"),
+    }
+}
+
+#[test]
+fn issuer_mint_overflow_denied() {
+    let issuer = Issuer::new(Resource::MissionTime, None);
+    let (issuer, mission) = minted(&issuer, 1, &Account::default());
+    let max = Quantity::Amount(I32F32::from_bits(i64::MAX));
+    match issuer.mint(max, &mission) {
+        Issuance::Denied(deficit) => assert_eq!(deficit, hashmap![Resource::MissionTime => max]),
+        _ => panic!("mint approved"),
+    }
+    let rich = common::account(&[(Resource::MissionTime, i32::MAX)]);
+    match Issuer::new(Resource::MissionTime, None).mint(fixed_amount(1), &rich) {
+        Issuance::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Resource::MissionTime => fixed_amount(1)])
+        }
+        _ => panic!("mint approved"),
+    }
+    assert_eq!(issuer.supply(), fixed_amount(1));
+}
@@ -0,0 +1,111 @@
+extern crate exgine;
+
+use exgine::{
+    account::{fixed_amount, Account},
+    asset, hashmap, invariant,
+    map::Map,
+    multi::{Leg, MultiRate, MultiTranx},
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Item {
+    Coin,
+    Ore,
+    Sword,
+}
+
+impl asset::Asset for Item {}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Role {
+    Buyer,
+    Supplier,
+    RoyaltyHolder,
+}
+
+impl asset::Asset for Role {}
+
+fn leg(from: Role, to: Role, asset: Item, amount: i32) -> Leg<Role, Item> {
+    Leg {
+        from,
+        to,
+        asset,
+        amount: fixed_amount(amount),
+    }
+}
+
+// The buyer pays the supplier for a sword forged from its ore, and the
+// supplier pays a royalty on the design.
+fn crafting() -> MultiRate<Role, Item> {
+    MultiRate {
+        legs: vec![
+            leg(Role::Buyer, Role::Supplier, Item::Coin, 10),
+            leg(Role::Supplier, Role::Buyer, Item::Sword, 1),
+            leg(Role::Supplier, Role::RoyaltyHolder, Item::Coin, 2),
+        ],
+    }
+}
+
+fn parties() -> Map<Role, Account<Item>> {
+    hashmap![
+        Role::Buyer => Account::from(hashmap![Item::Coin => fixed_amount(25)]),
+        Role::Supplier => Account::from(hashmap![Item::Sword => fixed_amount(3), Item::Ore => fixed_amount(7)]),
+        Role::RoyaltyHolder => Account::default(),
+    ]
+}
+
+#[test]
+fn multi_exchange_three_parties() {
+    let rate = crafting();
+    assert_eq!(
+        rate.roles(),
+        vec![&Role::Buyer, &Role::Supplier, &Role::RoyaltyHolder]
+    );
+    let before = parties();
+    match rate.exchange(fixed_amount(2), &before) {
+        MultiTranx::Approved(after) => {
+            assert_eq!(
+                after[&Role::Buyer],
+                Account::from(
+                    hashmap![Item::Coin => fixed_amount(5), Item::Sword => fixed_amount(2)]
+                )
+            );
+            assert_eq!(
+                after[&Role::Supplier],
+                Account::from(hashmap![
+                    Item::Coin => fixed_amount(16),
+                    Item::Sword => fixed_amount(1),
+                    Item::Ore => fixed_amount(7),
+                ])
+            );
+            assert_eq!(
+                after[&Role::RoyaltyHolder],
+                Account::from(hashmap![Item::Coin => fixed_amount(4)])
+            );
+            assert!(invariant::conserved(before.values(), after.values()).is_ok());
+        }
+        _ => panic!("exchange denied"),
+    }
+}
+
+#[test]
+fn multi_exchange_validates_every_participant() {
+    let rate = crafting();
+    match rate.exchange(fixed_amount(4), &parties()) {
+        MultiTranx::Denied(deficit) => assert_eq!(
+            deficit,
+            hashmap![
+                Role::Buyer => hashmap![Item::Coin => fixed_amount(-15)],
+                Role::Supplier => hashmap![Item::Sword => fixed_amount(-1)],
+            ]
+        ),
+        _ => panic!("exchange approved"),
+    }
+
+    let mut parties = parties();
+    parties.remove(&Role::RoyaltyHolder);
+    match rate.exchange(fixed_amount(1), &parties) {
+        MultiTranx::Unbound(roles) => assert_eq!(roles, vec![Role::RoyaltyHolder]),
+        _ => panic!("exchange with unbound role"),
+    }
+}
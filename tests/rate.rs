@@ -1,13 +1,15 @@
+// Unmatched transactions fail the lifetime tests with `assert!(false)`.
+#![allow(clippy::assertions_on_constants)]
+
 extern crate exgine;
 
 use exgine::{
-    account::{self, fixed_amount    , Tranx},
-    asset, hashmap, rate,
+    account::{self, fixed_amount    , Quantity, Tranx, I32F32},
+    asset, hashmap, map::Map, rate::{self, Charge, Fee},
 };
-use std::collections::HashMap;
 use std::time::Instant;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Resource {
     Battery,
     RgbSensor,
@@ -15,7 +17,7 @@ pub enum Resource {
     PoseEstimation,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum Reward {
     Score,
     Token,
@@ -24,7 +26,7 @@ pub enum Reward {
     Policy,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum RobotMissionAsset {
     Resource(Resource),
     Reward(Reward),
@@ -35,7 +37,7 @@ pub enum RobotMissionAsset {
 
 impl asset::Asset for RobotMissionAsset {}
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RobotMissionMarket {
     MissionTimeWithResource,
     MissionTimeWithTrust,
@@ -61,7 +63,7 @@ fn agent_default() -> Account {
     ])
 }
 
-fn rates_default() -> HashMap<Market, Rate> {
+fn rates_default() -> Map<Market, Rate> {
     hashmap![
         Market::MissionTimeWithResource =>
         Rate {
@@ -71,6 +73,7 @@ fn rates_default() -> HashMap<Market, Rate> {
                 Asset::Reward(Reward::Token) => fixed_amount(3),
                 Asset::Reward(Reward::Policy) => fixed_amount(1),
             ],
+            fee: None,
         },
     ]
 }
@@ -98,7 +101,7 @@ fn rate_buy_lifetime() {
     ]);
 
     match Account::exchange(rate, fixed_amount(1), &agent, &mission) {
-        Tranx::Approved(buyer, seller) => {
+        Tranx::Approved(buyer, seller, _) => {
             assert_eq!(res_seller, seller);
             assert_eq!(res_buyer, buyer);
         }
@@ -129,10 +132,385 @@ fn rate_buy_lifetime_quantity() {
     ]);
 
     match Account::exchange(rate, fixed_amount(2), &agent, &mission) {
-        Tranx::Approved(buyer, seller) => {
+        Tranx::Approved(buyer, seller, _) => {
             assert_eq!(res_seller, seller);
             assert_eq!(res_buyer, buyer);
         }
         _ => assert!(false),
     }
 }
+
+fn rate_with_fee(charge: Charge) -> Rate {
+    Rate {
+        credit: hashmap![Asset::MissionTime => fixed_amount(1)],
+        debit: hashmap![Asset::Reward(Reward::Token) => fixed_amount(10)],
+        fee: Some(Fee {
+            asset: Asset::Reward(Reward::Token),
+            charge,
+        }),
+    }
+}
+
+#[test]
+fn rate_flat_fee() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let treasury = Account::from(hashmap![Asset::Reward(Reward::Token) => fixed_amount(5)]);
+
+    match Account::exchange_with_fee(&rate, fixed_amount(3), &agent_default(), &mission_default(), &treasury) {
+        Tranx::Approved(buyer, seller, treasury) => {
+            assert_eq!(buyer.quantity(&Asset::Reward(Reward::Token)), fixed_amount(9968));
+            assert_eq!(seller.quantity(&Asset::Reward(Reward::Token)), fixed_amount(30));
+            assert_eq!(treasury, Account::from(hashmap![Asset::Reward(Reward::Token) => fixed_amount(7)]));
+        }
+        _ => panic!("exchange denied"),
+    }
+}
+
+#[test]
+fn rate_percent_fee() {
+    let rate = rate_with_fee(Charge::Percent(fixed_amount(5)));
+
+    match Account::exchange(&rate, fixed_amount(4), &agent_default(), &mission_default()) {
+        Tranx::Approved(buyer, _, fees) => {
+            assert_eq!(buyer.quantity(&Asset::Reward(Reward::Token)), fixed_amount(9958));
+            assert_eq!(fees, Account::from(hashmap![Asset::Reward(Reward::Token) => fixed_amount(2)]));
+        }
+        _ => panic!("exchange denied"),
+    }
+}
+
+#[test]
+fn rate_fee_percent_large() {
+    let half = Charge::Percent(fixed_amount(50));
+    assert_eq!(half.amount(fixed_amount(1_000_000_000)), fixed_amount(500_000_000));
+    assert_eq!(half.amount(fixed_amount(-1_000_000_000)), fixed_amount(-500_000_000));
+    let tenfold = Charge::Percent(fixed_amount(1000));
+    assert_eq!(tenfold.amount(fixed_amount(1_000_000_000)), Quantity::Amount(I32F32::from_bits(i64::MAX)));
+}
+
+#[test]
+fn rate_fee_percent_not_debited() {
+    let rate = Rate {
+        fee: Some(Fee {
+            asset: Asset::Trust,
+            charge: Charge::Percent(fixed_amount(10)),
+        }),
+        ..rate_with_fee(Charge::Flat(fixed_amount(0)))
+    };
+
+    match Account::exchange(&rate, fixed_amount(4), &agent_default(), &mission_default()) {
+        Tranx::Approved(buyer, _, fees) => {
+            assert_eq!(buyer.quantity(&Asset::Trust), agent_default().quantity(&Asset::Trust));
+            assert_eq!(fees.quantity(&Asset::Trust), fixed_amount(0));
+        }
+        _ => panic!("exchange denied"),
+    }
+}
+
+#[test]
+fn rate_fee_denied() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(1)));
+    let agent = Account::from(hashmap![Asset::Reward(Reward::Token) => fixed_amount(10)]);
+
+    match Account::exchange(&rate, fixed_amount(1), &agent, &mission_default()) {
+        Tranx::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(-1)]);
+        }
+        _ => panic!("exchange approved"),
+    }
+}
+
+#[test]
+fn rate_fee_negative_denied() {
+    let token = Asset::Reward(Reward::Token);
+    for (charge, fee) in [
+        (Charge::Flat(fixed_amount(-1)), fixed_amount(-1)),
+        (Charge::Percent(fixed_amount(-5)), fixed_amount(-2)),
+    ] {
+        let rate = rate_with_fee(charge);
+        let (mut agent, mut mission, mut treasury) =
+            (agent_default(), mission_default(), Account::from(hashmap![token => fixed_amount(100)]));
+        match Account::exchange_with_fee(&rate, fixed_amount(4), &agent, &mission, &treasury) {
+            Tranx::Denied(deficit) => assert_eq!(deficit, hashmap![token => fee]),
+            _ => panic!("exchange approved"),
+        }
+        assert_eq!(Account::quote(&rate, fixed_amount(4), &agent, &mission).deficit, hashmap![token => fee]);
+        assert!(!Account::can_afford(&rate, fixed_amount(4), &agent, &mission));
+        assert_eq!(
+            Account::exchange_in_place(&rate, fixed_amount(4), &mut agent, &mut mission, &mut treasury),
+            Err(hashmap![token => fee])
+        );
+        assert_eq!(treasury.quantity(&token), fixed_amount(100));
+    }
+}
+
+#[test]
+fn rate_fill_max_buyer_limited() {
+    let rates = rates_default();
+    let rate = rates.get(&Market::MissionTimeWithResource).unwrap();
+    let (filled, tranx) = Account::fill_max(
+        rate,
+        fixed_amount(5000),
+        &agent_default(),
+        &mission_default(),
+        &Account::default(),
+    );
+    // 9 Prediction per MissionTime: the last fixed-point step below 10000 / 9.
+    let max = I32F32::from_bits((10000i64 << 32) / 9);
+    assert_eq!(filled, Quantity::Amount(max));
+    match tranx {
+        Tranx::Approved(buyer, ..) => {
+            let Quantity::Amount(left) = buyer.quantity(&Asset::Reward(Reward::Prediction));
+            assert!(left >= 0 && left < I32F32::from_bits(9));
+        }
+        _ => panic!("fill denied"),
+    }
+}
+
+#[test]
+fn rate_fill_max_seller_limited() {
+    let rates = rates_default();
+    let rate = rates.get(&Market::MissionTimeWithResource).unwrap();
+    let mission = Account::from(hashmap![Asset::MissionTime => fixed_amount(100)]);
+    match Account::fill_max(rate, fixed_amount(500), &agent_default(), &mission, &Account::default()) {
+        (filled, Tranx::Approved(_, seller, _)) => {
+            assert_eq!(filled, fixed_amount(100));
+            assert_eq!(seller.quantity(&Asset::MissionTime), fixed_amount(0));
+        }
+        _ => panic!("fill denied"),
+    }
+}
+
+#[test]
+fn rate_fill_max_nothing() {
+    let rates = rates_default();
+    let rate = rates.get(&Market::MissionTimeWithResource).unwrap();
+    let agent = Account::from(hashmap![Asset::MissionTime => fixed_amount(1)]);
+    match Account::fill_max(rate, fixed_amount(1), &agent, &mission_default(), &Account::default()) {
+        (filled, Tranx::Denied(deficit)) => {
+            assert_eq!(filled, fixed_amount(0));
+            assert_eq!(deficit.len(), 3);
+        }
+        _ => panic!("fill approved"),
+    }
+}
+
+#[test]
+fn rate_quote() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let agent = agent_default();
+    let mission = mission_default();
+
+    let quote = Account::quote(&rate, fixed_amount(3), &agent, &mission);
+    assert!(quote.approved());
+    assert_eq!(quote.debit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(30)]);
+    assert_eq!(quote.credit, hashmap![Asset::MissionTime => fixed_amount(3)]);
+    assert_eq!(quote.fees, hashmap![Asset::Reward(Reward::Token) => fixed_amount(2)]);
+    match Account::exchange(&rate, fixed_amount(3), &agent, &mission) {
+        Tranx::Approved(buyer, seller, _) => {
+            for (asset, quantity) in &quote.buyer {
+                assert_eq!(buyer.quantity(asset), *quantity);
+            }
+            for (asset, quantity) in &quote.seller {
+                assert_eq!(seller.quantity(asset), *quantity);
+            }
+        }
+        _ => panic!("exchange denied"),
+    }
+
+    let quote = Account::quote(&rate, fixed_amount(1000), &agent, &mission);
+    assert!(!quote.approved());
+    assert_eq!(quote.deficit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(-2)]);
+}
+
+#[test]
+fn rate_can_afford() {
+    let rates = rates_default();
+    let rate = rates.get(&Market::MissionTimeWithResource).unwrap();
+    let (agent, mission) = (agent_default(), mission_default());
+    assert!(Account::can_afford(rate, fixed_amount(1111), &agent, &mission));
+    assert!(!Account::can_afford(rate, fixed_amount(1112), &agent, &mission));
+
+    let rate = rate_with_fee(Charge::Percent(fixed_amount(10)));
+    assert!(Account::can_afford(&rate, fixed_amount(909), &agent, &mission));
+    assert!(!Account::can_afford(&rate, fixed_amount(910), &agent, &mission));
+}
+
+#[test]
+fn rate_exchange_in_place() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let (mut agent, mut mission, mut treasury) = (agent_default(), mission_default(), Account::default());
+
+    assert!(Account::exchange_in_place(&rate, fixed_amount(3), &mut agent, &mut mission, &mut treasury).is_ok());
+    assert_eq!(agent.quantity(&Asset::Reward(Reward::Token)), fixed_amount(9968));
+    assert_eq!(agent.quantity(&Asset::MissionTime), fixed_amount(3));
+    assert_eq!(mission.quantity(&Asset::MissionTime), fixed_amount(999997));
+    assert_eq!(treasury.quantity(&Asset::Reward(Reward::Token)), fixed_amount(2));
+
+    let before = agent.clone();
+    match Account::exchange_in_place(&rate, fixed_amount(1000), &mut agent, &mut mission, &mut treasury) {
+        Err(deficit) => assert_eq!(deficit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(-34)]),
+        Ok(()) => panic!("exchange approved"),
+    }
+    assert_eq!(agent, before);
+}
+
+#[test]
+fn rate_credit_line() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let bank = agent_default().with_credit(Asset::Reward(Reward::Token), fixed_amount(500));
+    assert_eq!(bank.credit(&Asset::Reward(Reward::Token)), fixed_amount(500));
+    assert_eq!(bank.credit(&Asset::Trust), fixed_amount(0));
+    assert_eq!(bank, agent_default());
+
+    assert!(Account::can_afford(&rate, fixed_amount(1049), &bank, &mission_default()));
+    match Account::exchange(&rate, fixed_amount(1049), &bank, &mission_default()) {
+        Tranx::Approved(buyer, ..) => {
+            assert_eq!(buyer.quantity(&Asset::Reward(Reward::Token)), fixed_amount(-492));
+            assert_eq!(buyer.credit(&Asset::Reward(Reward::Token)), fixed_amount(500));
+        }
+        _ => panic!("exchange denied"),
+    }
+
+    assert!(!Account::can_afford(&rate, fixed_amount(1051), &bank, &mission_default()));
+    match Account::exchange(&rate, fixed_amount(1051), &bank, &mission_default()) {
+        Tranx::Denied(deficit) => {
+            assert_eq!(deficit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(-12)]);
+        }
+        _ => panic!("exchange approved"),
+    }
+    let quote = Account::quote(&rate, fixed_amount(1051), &bank, &mission_default());
+    assert_eq!(quote.deficit, hashmap![Asset::Reward(Reward::Token) => fixed_amount(-12)]);
+}
+
+#[test]
+fn rate_seller_credit_line() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let mission = Account::from(hashmap![Asset::MissionTime => fixed_amount(5)])
+        .with_credit(Asset::MissionTime, fixed_amount(3));
+    let agent = agent_default();
+
+    assert!(Account::can_afford(&rate, fixed_amount(8), &agent, &mission));
+    match Account::exchange(&rate, fixed_amount(8), &agent, &mission) {
+        Tranx::Approved(_, seller, _) => assert_eq!(seller.quantity(&Asset::MissionTime), fixed_amount(-3)),
+        _ => panic!("exchange denied"),
+    }
+
+    assert!(!Account::can_afford(&rate, fixed_amount(9), &agent, &mission));
+    match Account::exchange(&rate, fixed_amount(9), &agent, &mission) {
+        Tranx::Denied(deficit) => assert_eq!(deficit, hashmap![Asset::MissionTime => fixed_amount(-1)]),
+        _ => panic!("exchange approved"),
+    }
+    let quote = Account::quote(&rate, fixed_amount(9), &agent, &mission);
+    assert_eq!(quote.deficit, hashmap![Asset::MissionTime => fixed_amount(-1)]);
+    let (filled, _) = Account::fill_max(&rate, fixed_amount(9), &agent, &mission, &Account::default());
+    assert_eq!(filled, fixed_amount(8));
+
+    let mission = mission.with_credit(Asset::MissionTime, fixed_amount(-3));
+    assert_eq!(mission.credit(&Asset::MissionTime), fixed_amount(0));
+    assert!(mission.credit_lines().is_empty());
+}
+
+#[test]
+fn rate_hold_release_capture() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let token = Asset::Reward(Reward::Token);
+    let (mut agent, mut mission) = (agent_default(), mission_default());
+
+    assert!(agent.hold(&token, fixed_amount(9000)).is_ok());
+    assert_eq!(agent.quantity(&token), fixed_amount(10000));
+    assert_eq!(agent.held(&token), fixed_amount(9000));
+    assert_eq!(agent.available(&token), fixed_amount(1000));
+    assert_eq!(agent.hold(&token, fixed_amount(1001)), Err(hashmap![token => fixed_amount(-1)]));
+
+    assert!(Account::can_afford(&rate, fixed_amount(99), &agent, &mission));
+    match Account::exchange(&rate, fixed_amount(100), &agent, &mission) {
+        Tranx::Denied(deficit) => assert_eq!(deficit, hashmap![token => fixed_amount(-2)]),
+        _ => panic!("exchange approved"),
+    }
+
+    assert_eq!(agent.release(&token, fixed_amount(9001)), Err(hashmap![token => fixed_amount(-1)]));
+    assert!(agent.release(&token, fixed_amount(4000)).is_ok());
+    assert!(agent.capture(&token, fixed_amount(5000), &mut mission).is_ok());
+    assert_eq!(agent.quantity(&token), fixed_amount(5000));
+    assert_eq!(agent.available(&token), fixed_amount(5000));
+    assert!(agent.holds().is_empty());
+    assert_eq!(mission.quantity(&token), fixed_amount(5000));
+}
+
+#[test]
+fn rate_hold_non_positive() {
+    let token = Asset::Reward(Reward::Token);
+    let (mut agent, mut mission) = (agent_default(), mission_default());
+    assert!(agent.hold(&token, fixed_amount(100)).is_ok());
+
+    assert_eq!(agent.hold(&token, fixed_amount(-5)), Err(hashmap![token => fixed_amount(-5)]));
+    assert_eq!(agent.hold(&token, fixed_amount(0)), Err(hashmap![token => fixed_amount(0)]));
+    assert_eq!(agent.release(&token, fixed_amount(-5)), Err(hashmap![token => fixed_amount(-5)]));
+    assert_eq!(agent.capture(&token, fixed_amount(-5), &mut mission), Err(hashmap![token => fixed_amount(-5)]));
+    assert_eq!(agent.held(&token), fixed_amount(100));
+    assert_eq!(agent.quantity(&token), fixed_amount(10000));
+    assert_eq!(mission, mission_default());
+}
+
+#[test]
+fn rate_seller_hold() {
+    let rate = rate_with_fee(Charge::Flat(fixed_amount(2)));
+    let agent = agent_default();
+    let mut mission = mission_default();
+    assert!(mission.hold(&Asset::MissionTime, fixed_amount(999990)).is_ok());
+
+    assert!(Account::can_afford(&rate, fixed_amount(10), &agent, &mission));
+    assert!(!Account::can_afford(&rate, fixed_amount(11), &agent, &mission));
+    match Account::exchange(&rate, fixed_amount(11), &agent, &mission) {
+        Tranx::Denied(deficit) => assert_eq!(deficit, hashmap![Asset::MissionTime => fixed_amount(-1)]),
+        _ => panic!("exchange approved"),
+    }
+    let mut buyer = agent.clone();
+    let mut treasury = Account::default();
+    match Account::exchange_in_place(&rate, fixed_amount(11), &mut buyer, &mut mission, &mut treasury) {
+        Err(deficit) => assert_eq!(deficit, hashmap![Asset::MissionTime => fixed_amount(-1)]),
+        Ok(()) => panic!("exchange approved"),
+    }
+    assert_eq!(buyer, agent);
+}
+
+#[test]
+fn rate_recipe_produce() {
+    let recipe = rate::Recipe {
+        inputs: hashmap![
+            Asset::Resource(Resource::Battery) => fixed_amount(20),
+            Asset::Resource(Resource::RgbSensor) => fixed_amount(3),
+        ],
+        outputs: hashmap![Asset::Reward(Reward::Prediction) => fixed_amount(1)],
+    };
+    let robot = Account::from(hashmap![
+        Asset::Resource(Resource::Battery) => fixed_amount(100),
+        Asset::Resource(Resource::RgbSensor) => fixed_amount(12),
+    ]);
+
+    let robot = Account::produce(&recipe, fixed_amount(4), &robot).unwrap();
+    assert_eq!(robot, Account::from(hashmap![
+        Asset::Resource(Resource::Battery) => fixed_amount(20),
+        Asset::Resource(Resource::RgbSensor) => fixed_amount(0),
+        Asset::Reward(Reward::Prediction) => fixed_amount(4),
+    ]));
+
+    match Account::produce(&recipe, fixed_amount(2), &robot) {
+        Err(deficit) => assert_eq!(deficit, hashmap![
+            Asset::Resource(Resource::Battery) => fixed_amount(-20),
+            Asset::Resource(Resource::RgbSensor) => fixed_amount(-6),
+        ]),
+        Ok(_) => panic!("recipe approved"),
+    }
+
+    match Account::produce(&recipe, fixed_amount(-1), &robot) {
+        Err(deficit) => assert_eq!(deficit, hashmap![
+            Asset::Resource(Resource::Battery) => fixed_amount(-1),
+            Asset::Resource(Resource::RgbSensor) => fixed_amount(-1),
+            Asset::Reward(Reward::Prediction) => fixed_amount(-1),
+        ]),
+        Ok(_) => panic!("recipe approved"),
+    }
+    assert!(Account::produce(&recipe, fixed_amount(0), &robot).is_err());
+}
@@ -0,0 +1,97 @@
+extern crate exgine;
+
+mod common;
+
+use common::Resource;
+use exgine::{
+    account::{fixed_amount, Account, Quantity, I32F32},
+    hashmap,
+    stats::Distribution,
+};
+
+fn agents() -> Vec<(&'static str, Account<Resource>)> {
+    vec![
+        (
+            "scout",
+            Account::from(hashmap![Resource::Battery => fixed_amount(10)]),
+        ),
+        (
+            "miner",
+            Account::from(
+                hashmap![Resource::Battery => fixed_amount(40), Resource::Trust => fixed_amount(5)],
+            ),
+        ),
+        (
+            "hauler",
+            Account::from(hashmap![Resource::Battery => fixed_amount(30)]),
+        ),
+        (
+            "idle",
+            Account::from(hashmap![Resource::Trust => fixed_amount(5)]),
+        ),
+    ]
+}
+
+fn distribution(
+    asset: Resource,
+    agents: &[(&'static str, Account<Resource>)],
+) -> Distribution<&'static str> {
+    Distribution::of(
+        &asset,
+        agents.iter().map(|(name, account)| (*name, account)),
+    )
+}
+
+#[test]
+fn stats_battery() {
+    let agents = agents();
+    let battery = distribution(Resource::Battery, &agents);
+    assert_eq!(battery.total(), Some(fixed_amount(80)));
+    assert_eq!(battery.holder_count(), 3);
+    assert_eq!(
+        battery.top(2),
+        &[("miner", fixed_amount(40)), ("hauler", fixed_amount(30))]
+    );
+    assert_eq!(battery.median(), fixed_amount(20));
+    assert!((battery.gini() - 0.4375).abs() < 1e-9);
+}
+
+#[test]
+fn stats_even_and_concentrated() {
+    let agents = agents();
+    let trust = distribution(Resource::Trust, &agents);
+    assert_eq!(trust.total(), Some(fixed_amount(10)));
+    assert_eq!(trust.holder_count(), 2);
+    assert_eq!(trust.median(), Quantity::Amount(I32F32::from_num(2.5)));
+    assert!((trust.gini() - 0.5).abs() < 1e-9);
+
+    let equal = distribution(Resource::Battery, &agents[..1]);
+    assert_eq!(equal.gini(), 0.0);
+    assert_eq!(equal.top(5).len(), 1);
+}
+
+#[test]
+fn stats_large_totals() {
+    let holders: Vec<(usize, Account<Resource>)> = (0..10_000)
+        .map(|i| {
+            let battery = if i % 2 == 0 { 1_000_000 } else { 0 };
+            (
+                i,
+                Account::from(hashmap![Resource::Battery => fixed_amount(battery)]),
+            )
+        })
+        .collect();
+    let battery = Distribution::of(
+        &Resource::Battery,
+        holders.iter().map(|(i, account)| (*i, account)),
+    );
+    assert_eq!(battery.total(), None);
+    assert_eq!(battery.holder_count(), 5_000);
+    assert!((battery.gini() - 0.5).abs() < 1e-9);
+
+    let half = Distribution::of(
+        &Resource::Battery,
+        holders[..2_000].iter().map(|(i, account)| (*i, account)),
+    );
+    assert_eq!(half.total(), Some(fixed_amount(1_000_000_000)));
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "wasm")]
+
+extern crate exgine;
+
+use exgine::wasm::{exchange, exchange_with_fee, WasmAccount as Account, WasmRate as Rate};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::*;
+
+fn market() -> (Rate, Account, Account) {
+    let mut rate = Rate::new();
+    rate.set_credit("MissionTime", "1").unwrap();
+    rate.set_debit("Trust", "0.5").unwrap();
+    let mut mission = Account::new();
+    mission.set_quantity("MissionTime", "1000").unwrap();
+    let mut agent = Account::new();
+    agent.set_quantity("Trust", "10").unwrap();
+    (rate, agent, mission)
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test]
+fn wasm_exchange_approved() {
+    let (rate, agent, mission) = market();
+    let tranx = exchange(&rate, "4", &agent, &mission).unwrap();
+    assert!(tranx.approved());
+    let buyer = tranx.buyer().unwrap();
+    assert_eq!(buyer.quantity("MissionTime"), "4");
+    assert_eq!(buyer.quantity("Trust"), "8");
+    assert_eq!(tranx.seller().unwrap().quantity("Trust"), "2");
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen_test]
+fn wasm_exchange_denied() {
+    let (rate, agent, mission) = market();
+    let tranx = exchange(&rate, "21", &agent, &mission).unwrap();
+    assert!(!tranx.approved());
+    assert!(tranx.buyer().is_none());
+    assert_eq!(tranx.deficit("Trust"), "-0.5");
+    assert!(exchange(&rate, "lots", &agent, &mission).is_err());
+}
+
+// The host tests below stay off paths that build a `JsValue` (asset lists
+// and parse errors), since those need a JavaScript runtime.
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn wasm_quantities_host() {
+    let mut account = Account::new();
+    account.set_quantity("MissionTime", " 2.25 ").unwrap();
+    account.set_quantity("Trust", "-0.5").unwrap();
+    assert_eq!(account.quantity("MissionTime"), "2.25");
+    assert_eq!(account.quantity("Trust"), "-0.5");
+    assert_eq!(account.quantity("Battery"), "0");
+    account.set_quantity("MissionTime", "3").unwrap();
+    assert_eq!(account.quantity("MissionTime"), "3");
+
+    let (rate, ..) = market();
+    assert_eq!(rate.credit("MissionTime"), "1");
+    assert_eq!(rate.debit("Trust"), "0.5");
+    assert_eq!(rate.credit("Trust"), "0");
+    assert_eq!(rate.debit("MissionTime"), "0");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn wasm_exchange_approved_host() {
+    let (rate, agent, mission) = market();
+    let tranx = exchange(&rate, "4", &agent, &mission).unwrap();
+    assert!(tranx.approved());
+    assert_eq!(tranx.deficit("Trust"), "0");
+    let buyer = tranx.buyer().unwrap();
+    assert_eq!(buyer.quantity("MissionTime"), "4");
+    assert_eq!(buyer.quantity("Trust"), "8");
+    let seller = tranx.seller().unwrap();
+    assert_eq!(seller.quantity("MissionTime"), "996");
+    assert_eq!(seller.quantity("Trust"), "2");
+    assert_eq!(agent.quantity("Trust"), "10");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn wasm_exchange_denied_host() {
+    let (rate, agent, mission) = market();
+    let tranx = exchange(&rate, "21", &agent, &mission).unwrap();
+    assert!(!tranx.approved());
+    assert!(tranx.buyer().is_none());
+    assert!(tranx.seller().is_none());
+    assert_eq!(tranx.deficit("Trust"), "-0.5");
+    assert_eq!(tranx.deficit("MissionTime"), "0");
+
+    let mut broke = Account::new();
+    broke.set_quantity("MissionTime", "1").unwrap();
+    let tranx = exchange(&rate, "2", &agent, &broke).unwrap();
+    assert!(!tranx.approved());
+    assert_eq!(tranx.deficit("MissionTime"), "-1");
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn wasm_exchange_fee_host() {
+    let (mut rate, agent, mission) = market();
+    assert_eq!(rate.fee_asset(), None);
+    rate.set_percent_fee("Trust", "50").unwrap();
+    assert_eq!(rate.fee_asset(), Some("Trust".to_string()));
+    let mut treasury = Account::new();
+    treasury.set_quantity("Trust", "1").unwrap();
+
+    let tranx = exchange_with_fee(&rate, "4", &agent, &mission, &treasury).unwrap();
+    assert!(tranx.approved());
+    assert_eq!(tranx.buyer().unwrap().quantity("Trust"), "7");
+    assert_eq!(tranx.seller().unwrap().quantity("Trust"), "2");
+    assert_eq!(tranx.fee_account().unwrap().quantity("Trust"), "2");
+
+    rate.set_flat_fee("Trust", "3").unwrap();
+    let tranx = exchange(&rate, "15", &agent, &mission).unwrap();
+    assert!(tranx.fee_account().is_none());
+    assert_eq!(tranx.deficit("Trust"), "-0.5");
+}